@@ -0,0 +1,712 @@
+// Application state and key handling, kept free of any terminal I/O
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::PathBuf;
+
+use crate::{
+    config::{self, ArchConfig},
+    generate, logging,
+    questions::{self, Answer, Question, QuestionType},
+    size::Size,
+    system::SystemInfo,
+    theme::Theme,
+};
+
+// The screens of the menu, in the order they are shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Screen {
+    Splash,
+    Question,
+    Review,
+    Saving,
+    Done,
+}
+
+pub struct App {
+    pub screen: Screen,
+    pub config: ArchConfig,
+    pub questions: Vec<Question>,
+    pub current_question: usize,
+    pub selected_option: usize,
+    pub input_value: String,
+    pub filter: String,
+    // Options ticked on a multi select question
+    pub checked: Vec<String>,
+    // Why the last answer was refused, shown until the next key press
+    pub message: Option<String>,
+    // What was typed on the keyboard test line, and whether keys go there
+    pub test_input: String,
+    pub test_focus: bool,
+    // First line shown on the review screen
+    pub review_scroll: u16,
+    pub theme: Theme,
+    // Where the config is written
+    pub output: PathBuf,
+    // Set when writing the config failed, shown on the Saving screen
+    pub save_error: Option<String>,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new(output: PathBuf, system: &SystemInfo) -> Self {
+        App::with_questions(output, questions::default_questions(system))
+    }
+
+    pub fn with_questions(output: PathBuf, questions: Vec<Question>) -> Self {
+        App {
+            screen: Screen::Splash,
+            config: ArchConfig::default(),
+            questions,
+            current_question: 0,
+            selected_option: 0,
+            input_value: String::new(),
+            filter: String::new(),
+            checked: Vec::new(),
+            message: None,
+            test_input: String::new(),
+            test_focus: false,
+            review_scroll: 0,
+            theme: Theme::default(),
+            output,
+            save_error: None,
+            should_quit: false,
+        }
+    }
+
+    // The question being asked, None outside of the Question screen
+    pub fn question(&self) -> Option<&Question> {
+        match self.screen {
+            Screen::Question => self.questions.get(self.current_question),
+            _ => None,
+        }
+    }
+
+    // True while keys go into a text field, so '~' and '*' are typed rather than acted on
+    pub fn typing(&self) -> bool {
+        match self.question().map(|question| &question.question_type) {
+            Some(QuestionType::FreeText | QuestionType::Number { .. } | QuestionType::Size { .. }) => true,
+            Some(_) => self.test_focus,
+            None => false,
+        }
+    }
+
+    // The options of the current question after filtering
+    pub fn options(&self) -> Vec<String> {
+        self.question()
+            .map(|question| question.filtered_options(&self.config, &self.filter))
+            .unwrap_or_default()
+    }
+
+    // The answer as it stands, before Enter is pressed
+    pub fn draft_answer(&self) -> Answer {
+        match self.question().map(|question| &question.question_type) {
+            Some(QuestionType::MultiSelect { .. }) => Answer::List(self.checked.clone()),
+            _ => Answer::Text(self.input_value.clone()),
+        }
+    }
+
+    // Leave the splash screen, called on a key press or when it timed out
+    pub fn finish_splash(&mut self) {
+        if self.screen == Screen::Splash {
+            self.current_question = 0;
+            self.enter_question();
+        }
+    }
+
+    // Show the question at `current_question`, skipping the ones that don't apply
+    // to the answers so far. Moves on to the review once all were asked.
+    fn enter_question(&mut self) {
+        self.reset_input();
+        while let Some(question) = self.questions.get(self.current_question) {
+            if question.is_visible(&self.config) {
+                break;
+            }
+            self.current_question += 1;
+        }
+        let Some(question) = self.questions.get(self.current_question) else {
+            self.screen = Screen::Review;
+            return;
+        };
+        self.screen = Screen::Question;
+        // Fill in the default answer
+        let options = question.all_options(&self.config);
+        match question.default_value(&self.config) {
+            Some(Answer::Number(number)) => self.input_value = number.to_string(),
+            Some(Answer::Size(size)) => self.input_value = size.to_string(),
+            Some(Answer::Text(default)) => match question.question_type {
+                QuestionType::FreeText | QuestionType::Size { .. } => self.input_value = default,
+                _ => {
+                    if let Some(index) = options.iter().position(|option| *option == default) {
+                        self.selected_option = index;
+                    }
+                }
+            },
+            Some(Answer::Bool(flag)) => self.selected_option = if flag { 0 } else { 1 },
+            Some(Answer::List(items)) => {
+                self.checked = items.into_iter().filter(|item| options.contains(item)).collect();
+            }
+            None => {}
+        }
+    }
+
+    // Write the config, moving on to Done or keeping the error for the Saving screen
+    pub fn save(&mut self) {
+        let files_dir = generate::output_dir(&self.output);
+        let saved = config::save_config(&self.config, &self.output)
+            .and_then(|()| generate::write_files(&self.config, &files_dir));
+        match saved {
+            Ok(()) => {
+                self.save_error = None;
+                self.screen = Screen::Done;
+            }
+            Err(e) => {
+                log_error!("failed to write config to {}: {}", self.output.display(), e);
+                self.save_error = Some(e.to_string());
+            }
+        }
+    }
+
+    // True when the runner should try to write the config
+    pub fn wants_save(&self) -> bool {
+        self.screen == Screen::Saving && self.save_error.is_none()
+    }
+
+    fn quit(&mut self) {
+        log_info!("quit on the {:?} screen", self.screen);
+        self.should_quit = true;
+    }
+
+    // Reset the per question input state
+    fn reset_input(&mut self) {
+        self.selected_option = 0;
+        self.input_value.clear();
+        self.filter.clear();
+        self.checked.clear();
+        self.message = None;
+        self.test_input.clear();
+        self.test_focus = false;
+    }
+
+    // The most options the current multi select question takes
+    fn max_checked(&self) -> Option<usize> {
+        match self.question()?.question_type {
+            QuestionType::MultiSelect { max, .. } => max,
+            _ => None,
+        }
+    }
+
+    // Refuse ticking more than the question allows
+    fn check_room(&mut self, count: usize) -> bool {
+        match self.max_checked() {
+            Some(max) if count > max => {
+                self.message = Some(format!("Select at most {}", max));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    // Tick or untick the highlighted option of a multi select question
+    fn toggle_selected(&mut self) {
+        let Some(option) = self.options().into_iter().nth(self.selected_option) else {
+            return;
+        };
+        match self.checked.iter().position(|checked| *checked == option) {
+            Some(index) => {
+                self.checked.remove(index);
+            }
+            None => {
+                if self.check_room(self.checked.len() + 1) {
+                    self.checked.push(option);
+                }
+            }
+        }
+    }
+
+    // Tick every option matching the filter
+    fn check_all(&mut self) {
+        let added: Vec<String> = self
+            .options()
+            .into_iter()
+            .filter(|option| !self.checked.contains(option))
+            .collect();
+        if self.check_room(self.checked.len() + added.len()) {
+            self.checked.extend(added);
+        }
+    }
+
+    // Untick every option matching the filter
+    fn check_none(&mut self) {
+        let shown = self.options();
+        self.checked.retain(|option| !shown.contains(option));
+    }
+
+    // Store the answer to the current question and move on
+    fn submit_answer(&mut self) {
+        let Some(question) = self.questions.get(self.current_question) else {
+            return;
+        };
+        let answer = match &question.question_type {
+            QuestionType::MultipleChoice { .. } => question
+                .filtered_options(&self.config, &self.filter)
+                .get(self.selected_option)
+                .map(|option| Answer::Text(option.clone()))
+                .ok_or_else(|| format!("No option matches '{}'", self.filter)),
+            QuestionType::MultiSelect { min, max, .. } => {
+                if self.checked.len() < *min {
+                    Err(format!("Select at least {} with Space", min))
+                } else if let Some(max) = max.filter(|max| self.checked.len() > *max) {
+                    Err(format!("Select at most {}", max))
+                } else {
+                    // Keep the order of the options, not the order they were ticked in
+                    let checked = question
+                        .all_options(&self.config)
+                        .into_iter()
+                        .filter(|option| self.checked.contains(option))
+                        .collect();
+                    Ok(Answer::List(checked))
+                }
+            }
+            QuestionType::FreeText => Ok(Answer::Text(self.input_value.clone())),
+            QuestionType::Boolean => Ok(Answer::Bool(self.selected_option == 0)),
+            QuestionType::Number { min, max, .. } => match self.input_value.trim().parse::<i64>() {
+                Ok(number) if (*min..=*max).contains(&number) => Ok(Answer::Number(number)),
+                _ => Err(format!("Enter a number from {} to {}", min, max)),
+            },
+            QuestionType::Size { allow_percent } => match self.input_value.parse::<Size>() {
+                Ok(Size::Percent(_)) if !allow_percent => Err("Enter a size like 512M or 8G".to_string()),
+                Ok(size) => Ok(Answer::Size(size)),
+                Err(message) => Err(message),
+            },
+        };
+        // Refused answers keep the question up with the reason in the footer
+        let answer = match answer.and_then(|answer| question.validate(&self.config, &answer).map(|_| answer)) {
+            Ok(answer) => answer,
+            Err(message) => {
+                log_warn!("{}: {}", question.prompt, message);
+                self.message = Some(message);
+                return;
+            }
+        };
+        let value = answer.clone().text();
+        log_info!("answered {} = {}", question.prompt, logging::redact(question.prompt, &value));
+        (question.apply)(&mut self.config, answer);
+
+        self.current_question += 1;
+        self.enter_question();
+        if self.screen == Screen::Review {
+            self.review_scroll = 0;
+        }
+    }
+
+    fn handle_question_key(&mut self, key: KeyEvent) {
+        let Some(question) = self.questions.get(self.current_question) else {
+            return;
+        };
+        self.message = None;
+        // Tab moves between the options and the keyboard test line
+        if question.test_line {
+            match key.code {
+                KeyCode::Tab => {
+                    self.test_focus = !self.test_focus;
+                    return;
+                }
+                KeyCode::Char(c) if self.test_focus => {
+                    self.test_input.push(c);
+                    return;
+                }
+                KeyCode::Backspace if self.test_focus => {
+                    self.test_input.pop();
+                    return;
+                }
+                _ => {}
+            }
+        }
+        // Ctrl+A and Ctrl+N tick all or none of the shown options
+        if let QuestionType::MultiSelect { .. } = question.question_type {
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                match key.code {
+                    KeyCode::Char('a') => self.check_all(),
+                    KeyCode::Char('n') => self.check_none(),
+                    _ => {}
+                }
+                return;
+            }
+        }
+        // Up and Down step numbers
+        if let QuestionType::Number { min, max, step } = question.question_type {
+            let current = self.input_value.trim().parse::<i64>().unwrap_or(min);
+            match key.code {
+                KeyCode::Up => {
+                    self.input_value = current.saturating_add(step).clamp(min, max).to_string();
+                    return;
+                }
+                KeyCode::Down => {
+                    self.input_value = current.saturating_sub(step).clamp(min, max).to_string();
+                    return;
+                }
+                _ => {}
+            }
+        }
+        match key.code {
+            KeyCode::Enter => self.submit_answer(),
+            // Handle up and down arrow keys
+            KeyCode::Up => self.selected_option = self.selected_option.saturating_sub(1),
+            KeyCode::Down => {
+                let option_count = question.filtered_options(&self.config, &self.filter).len();
+                if self.selected_option + 1 < option_count {
+                    self.selected_option += 1;
+                }
+            }
+            KeyCode::Char(c) => match question.question_type {
+                QuestionType::FreeText | QuestionType::Size { .. } => self.input_value.push(c),
+                QuestionType::Number { min, .. } => {
+                    if c.is_ascii_digit() || (c == '-' && min < 0 && self.input_value.is_empty()) {
+                        self.input_value.push(c);
+                    }
+                }
+                QuestionType::MultiSelect { .. } if c == ' ' => self.toggle_selected(),
+                QuestionType::MultipleChoice { .. } | QuestionType::MultiSelect { .. } => {
+                    self.filter.push(c);
+                    self.selected_option = 0;
+                }
+                QuestionType::Boolean => {}
+            },
+            KeyCode::Backspace => match question.question_type {
+                QuestionType::FreeText | QuestionType::Number { .. } | QuestionType::Size { .. } => {
+                    self.input_value.pop();
+                }
+                QuestionType::MultipleChoice { .. } | QuestionType::MultiSelect { .. } => {
+                    self.filter.pop();
+                    self.selected_option = 0;
+                }
+                QuestionType::Boolean => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn handle_review_key(&mut self, key: KeyEvent) {
+        match key.code {
+            // The drawing code keeps the scroll inside the text
+            KeyCode::Up => self.review_scroll = self.review_scroll.saturating_sub(1),
+            KeyCode::Down => self.review_scroll = self.review_scroll.saturating_add(1),
+            KeyCode::PageUp => self.review_scroll = self.review_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.review_scroll = self.review_scroll.saturating_add(10),
+            KeyCode::Enter => {
+                self.save_error = None;
+                self.screen = Screen::Saving;
+            }
+            // Go back and answer the last question again
+            KeyCode::Backspace | KeyCode::Left => {
+                let last = (0..self.questions.len())
+                    .rev()
+                    .find(|&i| self.questions[i].is_visible(&self.config));
+                if let Some(last) = last {
+                    self.current_question = last;
+                    self.enter_question();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_saving_key(&mut self, key: KeyEvent) {
+        if self.save_error.is_none() {
+            return;
+        }
+        match key.code {
+            // Clearing the error makes the runner try again
+            KeyCode::Char('r') | KeyCode::Enter => self.save_error = None,
+            KeyCode::Esc => {
+                log_warn!("quit without saving the config");
+                self.quit();
+            }
+            _ => {}
+        }
+    }
+}
+
+// Update the app for a key press
+pub fn handle_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        // Quit from any screen, Ctrl+C also works while typing
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return app.quit(),
+        KeyCode::Char('~') if !app.typing() => return app.quit(),
+        // Cycle through themes
+        KeyCode::Char('*') if app.screen != Screen::Saving && !app.typing() => {
+            app.theme = app.theme.next();
+            log_debug!("theme changed");
+            return;
+        }
+        _ => {}
+    }
+    match app.screen {
+        Screen::Splash => app.finish_splash(),
+        Screen::Question => app.handle_question_key(key),
+        Screen::Review => app.handle_review_key(key),
+        Screen::Saving => app.handle_saving_key(key),
+        Screen::Done => app.should_quit = true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) {
+        handle_key(app, KeyEvent::from(code));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    // Accept the defaults until the question with this prompt comes up
+    fn skip_to(app: &mut App, prompt: &str) {
+        while app.question().unwrap().prompt != prompt {
+            press(app, KeyCode::Enter);
+        }
+    }
+
+    fn app_at_questions() -> App {
+        let mut app = App::new(PathBuf::from("unused.toml"), &SystemInfo::default());
+        press(&mut app, KeyCode::Enter);
+        app
+    }
+
+    #[test]
+    fn splash_moves_to_first_question() {
+        let mut app = App::new(PathBuf::from("unused.toml"), &SystemInfo::default());
+        assert_eq!(app.screen, Screen::Splash);
+        press(&mut app, KeyCode::Char('x'));
+        assert_eq!(app.screen, Screen::Question);
+        assert_eq!(app.current_question, 0);
+    }
+
+    #[test]
+    fn free_text_answer_is_stored() {
+        let mut app = app_at_questions();
+        type_text(&mut app, "archbox");
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.hostname, "archbo");
+        assert_eq!(app.current_question, 1);
+        assert!(app.input_value.is_empty());
+    }
+
+    #[test]
+    fn filter_narrows_choices_and_selection() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "Timezone Region");
+        type_text(&mut app, "euro");
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.selected_option, 0, "only one option matches");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.question().unwrap().prompt, "Timezone City");
+        assert_eq!(app.options(), vec!["London"]);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.timezone, "Europe/London");
+    }
+
+    #[test]
+    fn no_match_keeps_the_question() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "Timezone Region");
+        type_text(&mut app, "zzz");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.question().unwrap().prompt, "Timezone Region");
+    }
+
+    #[test]
+    fn current_timezone_is_preselected() {
+        let system = SystemInfo {
+            current_timezone: Some("Asia/Tokyo".to_string()),
+            ..SystemInfo::default()
+        };
+        let mut app = App::new(PathBuf::from("unused.toml"), &system);
+        press(&mut app, KeyCode::Enter);
+        skip_to(&mut app, "Timezone Region");
+        assert_eq!(app.options()[app.selected_option], "Asia");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.options()[app.selected_option], "Tokyo");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.timezone, "Asia/Tokyo");
+    }
+
+    #[test]
+    fn multi_select_needs_a_minimum() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "Locales");
+        assert_eq!(app.checked, vec!["en_US.UTF-8 UTF-8"]);
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.message.is_some());
+        assert_eq!(app.question().unwrap().prompt, "Locales");
+        type_text(&mut app, "ja ");
+        press(&mut app, KeyCode::Enter);
+        // A single locale is LANG without asking
+        assert_eq!(app.config.locale.lang, "ja_JP.UTF-8");
+        assert_eq!(app.question().unwrap().prompt, "Locale Overrides");
+    }
+
+    #[test]
+    fn multi_select_all_none_and_max() {
+        let question = Question::new(
+            "Groups",
+            QuestionType::MultiSelect {
+                options: questions::Choices::Fixed(vec!["a1".into(), "a2".into(), "b1".into()]),
+                min: 0,
+                max: Some(2),
+            },
+            |config, answer| config.user_groups = answer.list(),
+        );
+        let mut app = App::with_questions(PathBuf::from("unused.toml"), vec![question]);
+        press(&mut app, KeyCode::Enter);
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        handle_key(&mut app, ctrl('a'));
+        assert!(app.checked.is_empty(), "three options don't fit in two");
+        assert!(app.message.is_some());
+        type_text(&mut app, "a");
+        handle_key(&mut app, ctrl('a'));
+        assert_eq!(app.checked, vec!["a1", "a2"]);
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.checked.len(), 2, "max reached");
+        type_text(&mut app, "a");
+        handle_key(&mut app, ctrl('n'));
+        assert!(app.checked.is_empty());
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.user_groups, vec!["b1"]);
+    }
+
+    #[test]
+    fn numbers_step_and_check_the_range() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "Format Type");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.question().unwrap().prompt, "Btrfs Compression Level");
+        assert_eq!(app.input_value, "3");
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.input_value, "4");
+        type_text(&mut app, "x0");
+        assert_eq!(app.input_value, "40");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.message.is_some());
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.input_value, "15");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.storage.btrfs_compression_level, Some(15));
+    }
+
+    #[test]
+    fn sizes_are_parsed() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "EFI Partition Size");
+        assert_eq!(app.input_value, "1G");
+        for _ in 0..2 {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_text(&mut app, "50%");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.message.is_some());
+        for _ in 0..3 {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_text(&mut app, "512m");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.storage.efi_size, Size::Bytes(512 << 20));
+    }
+
+    #[test]
+    fn keyboard_test_line_takes_keys_after_tab() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "Console Keymap");
+        press(&mut app, KeyCode::Tab);
+        type_text(&mut app, "äöü");
+        assert_eq!(app.test_input, "äöü");
+        assert!(app.filter.is_empty());
+        press(&mut app, KeyCode::Tab);
+        type_text(&mut app, "de");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.keyboard.console_keymap, "de");
+        // The X11 layout follows the console keymap
+        assert_eq!(app.options()[app.selected_option], "de");
+    }
+
+    #[test]
+    fn region_without_cities_skips_the_city() {
+        let mut app = app_at_questions();
+        skip_to(&mut app, "Timezone Region");
+        type_text(&mut app, "utc");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.timezone, "UTC");
+        assert_eq!(app.question().unwrap().prompt, "Locales");
+    }
+
+    #[test]
+    fn last_answer_goes_to_review_then_saving() {
+        let mut app = app_at_questions();
+        while app.screen == Screen::Question {
+            press(&mut app, KeyCode::Enter);
+        }
+        assert_eq!(app.screen, Screen::Review);
+        assert!(app.config.enable_ssh);
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.question().unwrap().prompt, "Services to Disable");
+        for c in "cups.service".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.config.services.disable, vec!["cups.service"]);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.wants_save());
+    }
+
+    #[test]
+    fn save_error_is_kept_until_retry() {
+        let mut app = app_at_questions();
+        app.screen = Screen::Saving;
+        app.save_error = Some("disk full".to_string());
+        assert!(!app.wants_save());
+        press(&mut app, KeyCode::Char('r'));
+        assert!(app.wants_save());
+    }
+
+    #[test]
+    fn tilde_quits_and_star_cycles_theme() {
+        let question = Question::new("Enable SSH", QuestionType::Boolean, |config, answer| {
+            config.enable_ssh = answer.flag()
+        });
+        let mut app = App::with_questions(PathBuf::from("unused.toml"), vec![question]);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('*'));
+        assert_eq!(app.theme.background, Theme::dark().background);
+        press(&mut app, KeyCode::Char('~'));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn tilde_and_star_are_typed_into_text_questions() {
+        let question = Question::new("Authorized Keys", QuestionType::FreeText, |config, answer| {
+            config.hostname = answer.text()
+        });
+        let mut app = App::with_questions(PathBuf::from("unused.toml"), vec![question]);
+        press(&mut app, KeyCode::Enter);
+        for c in "~/.ssh/*.pub".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert!(!app.should_quit);
+        assert_eq!(app.input_value, "~/.ssh/*.pub");
+        assert_eq!(app.theme.background, Theme::default().background);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(app.should_quit);
+    }
+}
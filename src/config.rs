@@ -0,0 +1,814 @@
+// The answers collected by the menu and how they are written to disk
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use crate::{logging, size::Size};
+
+// Default location of the generated configuration file
+pub const DEFAULT_OUTPUT: &str = "arch_config.toml";
+
+// Structure to hold the Arch Linux configuration
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct ArchConfig {
+    pub hostname: String,
+    pub username: String,
+    pub password: String,
+    pub timezone: String,
+    pub format_type: String,
+    pub bootloader: String,
+    pub enable_ssh: bool,
+    // Supplementary groups for the user, `wheel` gives sudo
+    #[serde(default)]
+    pub user_groups: Vec<String>,
+    // The curated groups picked, their packages are part of `packages`
+    #[serde(default)]
+    pub package_groups: Vec<String>,
+    // Every extra package for pacstrap, without duplicates
+    #[serde(default)]
+    pub packages: Vec<String>,
+    // Tables go last, toml can't write plain values after them
+    pub locale: LocaleConfig,
+    pub keyboard: KeyboardConfig,
+    pub storage: StorageConfig,
+    pub swap: SwapConfig,
+    pub kernel: KernelConfig,
+    pub mirrors: MirrorConfig,
+    pub network: NetworkConfig,
+    pub services: ServicesConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grub: Option<GrubConfig>,
+    #[serde(default, rename = "systemd-boot", skip_serializing_if = "Option::is_none")]
+    pub systemd_boot: Option<SystemdBootConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure_boot: Option<SecureBootConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<SshConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desktop: Option<DesktopConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aur: Option<AurConfig>,
+}
+
+// The [locale] table
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct LocaleConfig {
+    // LANG in /etc/locale.conf
+    pub lang: String,
+    // Lines for /etc/locale.gen, `en_US.UTF-8 UTF-8`
+    pub enabled: Vec<String>,
+    // LC_* variables that differ from LANG
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, String>,
+}
+
+impl ArchConfig {
+    // Serialize the configuration as it is written to arch_config.toml
+    pub fn to_toml(&self) -> io::Result<String> {
+        toml::to_string_pretty(self).map_err(io::Error::other)
+    }
+
+    // Flattened `key = value` pairs for the review screen, secrets are hidden.
+    // Nested tables show up as dotted keys (`ssh.port`).
+    pub fn review_lines(&self) -> Vec<(String, String)> {
+        let mut lines = Vec::new();
+        if let Ok(value) = toml::Value::try_from(self) {
+            flatten("", &value, &mut lines);
+        }
+        lines
+    }
+}
+
+// Walk a toml value, collecting leaf values under their dotted key
+fn flatten(prefix: &str, value: &toml::Value, lines: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, lines);
+            }
+        }
+        toml::Value::String(s) => {
+            lines.push((prefix.to_string(), logging::redact(prefix, s).to_string()))
+        }
+        toml::Value::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| match item {
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            lines.push((prefix.to_string(), items.join(", ")));
+        }
+        other => lines.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+// The [keyboard] table
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct KeyboardConfig {
+    // KEYMAP in /etc/vconsole.conf
+    pub console_keymap: String,
+    // XKB layout and variant for X11 and Wayland, an empty variant is the default one
+    pub xkb_layout: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub xkb_variant: String,
+}
+
+// The [storage] table, sizes and filesystem options
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StorageConfig {
+    pub efi_size: Size,
+    // zstd level for the btrfs `compress=` mount option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub btrfs_compression_level: Option<u8>,
+    // LUKS on the root partition, unlocked by the initramfs
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            efi_size: Size::gibibytes(1),
+            btrfs_compression_level: None,
+            encrypt: false,
+        }
+    }
+}
+
+// The [swap] table, where the system swaps to and whether it can hibernate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SwapConfig {
+    #[serde(rename = "type")]
+    pub kind: SwapKind,
+    // zram-generator's device size as a share of the RAM, can go past 100
+    pub zram_percent: u16,
+    pub zram_algorithm: ZramAlgorithm,
+    // For a swapfile or a swap partition
+    pub size: Size,
+    // Needs a swapfile or partition at least as big as the RAM
+    pub hibernate: bool,
+}
+
+impl Default for SwapConfig {
+    fn default() -> Self {
+        SwapConfig {
+            kind: SwapKind::Zram,
+            zram_percent: 50,
+            zram_algorithm: ZramAlgorithm::Zstd,
+            size: Size::gibibytes(8),
+            hibernate: false,
+        }
+    }
+}
+
+impl SwapConfig {
+    // Hibernation only works to a disk
+    pub fn can_hibernate(&self) -> bool {
+        matches!(self.kind, SwapKind::Swapfile | SwapKind::Partition)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SwapKind {
+    Zram,
+    Swapfile,
+    Partition,
+    None,
+}
+
+impl SwapKind {
+    pub const ALL: [SwapKind; 4] = [SwapKind::Zram, SwapKind::Swapfile, SwapKind::Partition, SwapKind::None];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SwapKind::Zram => "zram",
+            SwapKind::Swapfile => "swapfile",
+            SwapKind::Partition => "partition",
+            SwapKind::None => "none",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SwapKind> {
+        SwapKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ZramAlgorithm {
+    #[serde(rename = "zstd")]
+    Zstd,
+    #[serde(rename = "lz4")]
+    Lz4,
+    #[serde(rename = "lzo-rle")]
+    LzoRle,
+}
+
+impl ZramAlgorithm {
+    pub const ALL: [ZramAlgorithm; 3] = [ZramAlgorithm::Zstd, ZramAlgorithm::Lz4, ZramAlgorithm::LzoRle];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ZramAlgorithm::Zstd => "zstd",
+            ZramAlgorithm::Lz4 => "lz4",
+            ZramAlgorithm::LzoRle => "lzo-rle",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ZramAlgorithm> {
+        ZramAlgorithm::ALL.into_iter().find(|algorithm| algorithm.name() == name)
+    }
+}
+
+// The [kernel] table, the kernels installed and their command line
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KernelConfig {
+    // Packages, `linux`, `linux-lts`, ...
+    pub kernels: Vec<String>,
+    // The kernel the boot menu starts with, one of `kernels`
+    pub default: String,
+    pub boot_messages: BootMessages,
+    pub gpu: Gpu,
+    // What runs in the initramfs, it decides the mkinitcpio hooks
+    pub initramfs: Initramfs,
+    // Added by hand after the ones the answers imply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<String>,
+    // Kernel, initramfs and command line as one EFI file that systemd-boot finds by itself
+    #[serde(default)]
+    pub uki: bool,
+}
+
+impl Default for KernelConfig {
+    fn default() -> Self {
+        KernelConfig {
+            kernels: vec!["linux".to_string()],
+            default: "linux".to_string(),
+            boot_messages: BootMessages::Quiet,
+            gpu: Gpu::Generic,
+            initramfs: Initramfs::Systemd,
+            params: Vec::new(),
+            uki: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BootMessages {
+    Verbose,
+    Quiet,
+    Splash,
+}
+
+impl BootMessages {
+    pub const ALL: [BootMessages; 3] = [BootMessages::Verbose, BootMessages::Quiet, BootMessages::Splash];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BootMessages::Verbose => "verbose",
+            BootMessages::Quiet => "quiet",
+            BootMessages::Splash => "splash",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BootMessages> {
+        BootMessages::ALL.into_iter().find(|messages| messages.name() == name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Initramfs {
+    Systemd,
+    Busybox,
+}
+
+impl Initramfs {
+    pub const ALL: [Initramfs; 2] = [Initramfs::Systemd, Initramfs::Busybox];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Initramfs::Systemd => "systemd",
+            Initramfs::Busybox => "busybox",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Initramfs> {
+        Initramfs::ALL.into_iter().find(|initramfs| initramfs.name() == name)
+    }
+}
+
+// The graphics vendor, some need kernel parameters
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Gpu {
+    Generic,
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl Gpu {
+    pub const ALL: [Gpu; 4] = [Gpu::Generic, Gpu::Nvidia, Gpu::Amd, Gpu::Intel];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Gpu::Generic => "generic",
+            Gpu::Nvidia => "nvidia",
+            Gpu::Amd => "amd",
+            Gpu::Intel => "intel",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Gpu> {
+        Gpu::ALL.into_iter().find(|gpu| gpu.name() == name)
+    }
+}
+
+// The [mirrors] table, the reflector options
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MirrorConfig {
+    // ISO 3166 codes, none means mirrors worldwide
+    pub countries: Vec<String>,
+    pub protocols: Vec<String>,
+    // Only mirrors synced within this many hours
+    pub age: u32,
+    pub sort: MirrorSort,
+    // How many of the most recently synced mirrors to keep
+    pub count: u32,
+    // Taken from the local mirrorlist when mirrors are picked offline
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<String>,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        MirrorConfig {
+            countries: Vec::new(),
+            protocols: vec!["https".to_string()],
+            age: 12,
+            sort: MirrorSort::Rate,
+            count: 20,
+            servers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorSort {
+    Rate,
+    Score,
+    Age,
+    Delay,
+    Country,
+}
+
+impl MirrorSort {
+    pub const ALL: [MirrorSort; 5] =
+        [MirrorSort::Rate, MirrorSort::Score, MirrorSort::Age, MirrorSort::Delay, MirrorSort::Country];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MirrorSort::Rate => "rate",
+            MirrorSort::Score => "score",
+            MirrorSort::Age => "age",
+            MirrorSort::Delay => "delay",
+            MirrorSort::Country => "country",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<MirrorSort> {
+        MirrorSort::ALL.into_iter().find(|sort| sort.name() == name)
+    }
+}
+
+// The [network] table, the stack the installed system comes up with
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NetworkConfig {
+    pub backend: NetworkBackend,
+    pub interface: String,
+    pub dhcp: bool,
+    // Only for a static setup, the address with its prefix (`192.168.1.10/24`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub address: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub gateway: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wifi: Option<WifiConfig>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            backend: NetworkBackend::NetworkManager,
+            interface: "eth0".to_string(),
+            dhcp: true,
+            address: String::new(),
+            gateway: String::new(),
+            dns: Vec::new(),
+            wifi: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum NetworkBackend {
+    #[serde(rename = "networkmanager")]
+    NetworkManager,
+    #[serde(rename = "systemd-networkd")]
+    Networkd,
+    #[serde(rename = "iwd")]
+    Iwd,
+}
+
+impl NetworkBackend {
+    pub const ALL: [NetworkBackend; 3] = [NetworkBackend::NetworkManager, NetworkBackend::Networkd, NetworkBackend::Iwd];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NetworkBackend::NetworkManager => "NetworkManager",
+            NetworkBackend::Networkd => "systemd-networkd",
+            NetworkBackend::Iwd => "iwd",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<NetworkBackend> {
+        NetworkBackend::ALL.into_iter().find(|backend| backend.name() == name)
+    }
+}
+
+// The [network.wifi] table, an empty passphrase is an open network
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WifiConfig {
+    pub ssid: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub passphrase: String,
+}
+
+// The [services] table, systemd units to switch on or off on the installed system
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct ServicesConfig {
+    #[serde(default)]
+    pub enable: Vec<String>,
+    #[serde(default)]
+    pub disable: Vec<String>,
+}
+
+// The [grub] table, only there when it is the bootloader
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GrubConfig {
+    pub target: GrubTarget,
+    // Seconds the menu waits
+    pub timeout: u32,
+    // Lists Windows and other systems found on the disks
+    pub os_prober: bool,
+    // A directory in /usr/share/grub/themes, empty for the plain menu
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub theme: String,
+}
+
+impl Default for GrubConfig {
+    fn default() -> Self {
+        GrubConfig {
+            target: GrubTarget::Efi,
+            timeout: 5,
+            os_prober: false,
+            theme: String::new(),
+        }
+    }
+}
+
+// grub-install's --target
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum GrubTarget {
+    #[serde(rename = "x86_64-efi")]
+    Efi,
+    #[serde(rename = "i386-pc")]
+    Bios,
+}
+
+impl GrubTarget {
+    pub const ALL: [GrubTarget; 2] = [GrubTarget::Efi, GrubTarget::Bios];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GrubTarget::Efi => "x86_64-efi",
+            GrubTarget::Bios => "i386-pc",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GrubTarget> {
+        GrubTarget::ALL.into_iter().find(|target| target.name() == name)
+    }
+}
+
+// The [systemd-boot] table, only there when it is the bootloader
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SystemdBootConfig {
+    pub timeout: u32,
+    // Editing the kernel command line at boot, off locks out `init=/bin/sh`
+    pub editor: bool,
+}
+
+impl Default for SystemdBootConfig {
+    fn default() -> Self {
+        SystemdBootConfig { timeout: 3, editor: false }
+    }
+}
+
+// The [secure_boot] table, own keys enrolled and the boot files signed with sbctl
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SecureBootConfig {
+    // Enroll Microsoft's keys next to ours, firmware drivers on GPUs and the like are signed with them
+    pub microsoft_keys: bool,
+}
+
+impl Default for SecureBootConfig {
+    fn default() -> Self {
+        SecureBootConfig { microsoft_keys: true }
+    }
+}
+
+// The [ssh] table, only there when enable_ssh is
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SshConfig {
+    pub port: u16,
+    pub password_authentication: bool,
+    pub permit_root_login: RootLogin,
+    // Public keys allowed to log in as the user
+    #[serde(default)]
+    pub authorized_keys: Vec<String>,
+}
+
+impl Default for SshConfig {
+    fn default() -> Self {
+        SshConfig {
+            port: 22,
+            password_authentication: true,
+            permit_root_login: RootLogin::No,
+            authorized_keys: Vec::new(),
+        }
+    }
+}
+
+// PermitRootLogin in sshd_config
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RootLogin {
+    No,
+    ProhibitPassword,
+    Yes,
+}
+
+impl RootLogin {
+    pub const ALL: [RootLogin; 3] = [RootLogin::No, RootLogin::ProhibitPassword, RootLogin::Yes];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RootLogin::No => "no",
+            RootLogin::ProhibitPassword => "prohibit-password",
+            RootLogin::Yes => "yes",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RootLogin> {
+        RootLogin::ALL.into_iter().find(|login| login.name() == name)
+    }
+}
+
+// The [desktop] table, only there when a desktop profile is picked
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DesktopConfig {
+    pub profile: String,
+    pub display_manager: DisplayManager,
+    pub session: Session,
+    // Optional parts of the profile that were picked
+    #[serde(default)]
+    pub extras: Vec<String>,
+    // Everything the profile installs, worked out from the answers above
+    pub packages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayManager {
+    Gdm,
+    Sddm,
+    Lightdm,
+    None,
+}
+
+impl DisplayManager {
+    pub const ALL: [DisplayManager; 4] =
+        [DisplayManager::Gdm, DisplayManager::Sddm, DisplayManager::Lightdm, DisplayManager::None];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayManager::Gdm => "gdm",
+            DisplayManager::Sddm => "sddm",
+            DisplayManager::Lightdm => "lightdm",
+            DisplayManager::None => "none",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DisplayManager> {
+        DisplayManager::ALL.into_iter().find(|manager| manager.name() == name)
+    }
+
+    pub fn packages(self) -> &'static [&'static str] {
+        match self {
+            DisplayManager::Gdm => &["gdm"],
+            DisplayManager::Sddm => &["sddm"],
+            DisplayManager::Lightdm => &["lightdm", "lightdm-gtk-greeter"],
+            DisplayManager::None => &[],
+        }
+    }
+
+    // The unit starting it, empty for none
+    pub fn unit(self) -> &'static str {
+        match self {
+            DisplayManager::Gdm => "gdm.service",
+            DisplayManager::Sddm => "sddm.service",
+            DisplayManager::Lightdm => "lightdm.service",
+            DisplayManager::None => "",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Session {
+    X11,
+    Wayland,
+}
+
+impl Session {
+    pub fn name(self) -> &'static str {
+        match self {
+            Session::X11 => "X11",
+            Session::Wayland => "Wayland",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Session> {
+        [Session::X11, Session::Wayland].into_iter().find(|session| session.name() == name)
+    }
+
+    // Wayland compositors bring their own display server
+    pub fn packages(self) -> &'static [&'static str] {
+        match self {
+            Session::X11 => &["xorg-server"],
+            Session::Wayland => &[],
+        }
+    }
+}
+
+// The [aur] table, only there when an AUR helper is wanted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AurConfig {
+    pub helper: AurHelper,
+    // makepkg refuses to run as root, so the helper is built by this user
+    pub build_user: String,
+    // Installed with the helper once the system is up
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AurHelper {
+    Yay,
+    Paru,
+}
+
+impl AurHelper {
+    pub const ALL: [AurHelper; 2] = [AurHelper::Yay, AurHelper::Paru];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AurHelper::Yay => "yay",
+            AurHelper::Paru => "paru",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<AurHelper> {
+        AurHelper::ALL.into_iter().find(|helper| helper.name() == name)
+    }
+
+    // Where the PKGBUILD of the helper is cloned from
+    pub fn repo_url(self) -> String {
+        format!("https://aur.archlinux.org/{}.git", self.name())
+    }
+}
+
+// Serialize the configuration and write it to disk
+pub fn save_config(config: &ArchConfig, path: &Path) -> io::Result<()> {
+    let config_toml = config.to_toml()?;
+    write_private_atomic(path, config_toml.as_bytes())?;
+    log_info!("wrote config to {} ({} bytes)", path.display(), config_toml.len());
+    Ok(())
+}
+// Write a file readable only by the owner (it holds the password).
+// The data goes to a temp file next to the target which is then renamed over it,
+// so an interrupted write never leaves a half written config behind.
+// Any existing file is kept as `<path>.bak`.
+pub fn write_private_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "output path has no file name"))?
+        .to_string_lossy()
+        .into_owned();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let tmp_path = dir.join(format!(".{}.tmp", file_name));
+    let backup_path = dir.join(format!("{}.bak", file_name));
+
+    // Write and flush the temp file, then keep a backup of the previous config
+    let result = (|| {
+        let mut file = open_private(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        if path.exists() {
+            // Copied into a file that is private from the start, not chmodded after the copy
+            let mut backup = open_private(&backup_path)?;
+            io::copy(&mut fs::File::open(path)?, &mut backup)?;
+            backup.sync_all()?;
+            log_info!("kept previous config as {}", backup_path.display());
+        }
+        // Move the new config into place
+        fs::rename(&tmp_path, path)
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+// Truncate or create a file only the owner can read
+fn open_private(path: &Path) -> io::Result<fs::File> {
+    let file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    // The mode only applies on create, so force it for a stale file
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_config_parses() {
+        let example = include_str!("../arch_config.toml");
+        let config: ArchConfig = toml::from_str(example).unwrap();
+        assert_eq!(config.to_toml().unwrap(), example);
+    }
+
+    #[test]
+    fn review_hides_the_password() {
+        let config = ArchConfig {
+            password: "hunter2".to_string(),
+            ..ArchConfig::default()
+        };
+        let lines = config.review_lines();
+        let password = lines.iter().find(|(key, _)| key == "password").unwrap();
+        assert_eq!(password.1, "********");
+    }
+
+    #[test]
+    fn backup_is_private_and_failures_leave_no_temp_file() {
+        let dir = crate::tests::temp_dir("private-write");
+        let path = dir.join("arch_config.toml");
+        fs::write(&path, "old = true\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_private_atomic(&path, b"new = true\n").unwrap();
+        let backup = dir.join("arch_config.toml.bak");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "old = true\n");
+        assert_eq!(fs::metadata(&backup).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // A directory in the way of the backup
+        fs::remove_file(&backup).unwrap();
+        fs::create_dir(&backup).unwrap();
+        assert!(write_private_atomic(&path, b"newer = true\n").is_err());
+        assert!(!dir.join(".arch_config.toml.tmp").exists());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new = true\n");
+    }
+}
@@ -0,0 +1,135 @@
+// Desktop profiles: what each choice installs and the defaults it comes with
+use crate::{
+    config::{DesktopConfig, DisplayManager, Session},
+    packages,
+};
+
+pub struct Profile {
+    pub name: &'static str,
+    pub packages: &'static [&'static str],
+    pub display_manager: DisplayManager,
+    // The sessions it can run, the first one is the default
+    pub sessions: &'static [Session],
+    // Optional additions offered as a follow-up, name and packages
+    pub extras: &'static [(&'static str, &'static [&'static str])],
+}
+
+pub const PROFILES: [Profile; 7] = [
+    Profile {
+        name: "gnome",
+        packages: &["gnome"],
+        display_manager: DisplayManager::Gdm,
+        sessions: &[Session::Wayland, Session::X11],
+        extras: &[("gnome-extra", &["gnome-extra"]), ("gnome-tweaks", &["gnome-tweaks"])],
+    },
+    Profile {
+        name: "kde",
+        packages: &["plasma-meta", "konsole", "dolphin"],
+        display_manager: DisplayManager::Sddm,
+        sessions: &[Session::Wayland, Session::X11],
+        extras: &[("kde-applications", &["kde-applications-meta"]), ("kdeconnect", &["kdeconnect"])],
+    },
+    Profile {
+        name: "xfce",
+        packages: &["xfce4"],
+        display_manager: DisplayManager::Lightdm,
+        sessions: &[Session::X11],
+        extras: &[("xfce4-goodies", &["xfce4-goodies"]), ("network-manager-applet", &["network-manager-applet"])],
+    },
+    Profile {
+        name: "cinnamon",
+        packages: &["cinnamon", "gnome-terminal"],
+        display_manager: DisplayManager::Lightdm,
+        sessions: &[Session::X11],
+        extras: &[("nemo-fileroller", &["nemo-fileroller"])],
+    },
+    Profile {
+        name: "i3",
+        packages: &["i3-wm", "i3status", "dmenu", "xterm"],
+        display_manager: DisplayManager::Lightdm,
+        sessions: &[Session::X11],
+        extras: &[("i3lock", &["i3lock"]), ("picom", &["picom"])],
+    },
+    Profile {
+        name: "sway",
+        packages: &["sway", "swaybg", "swaylock", "foot", "wmenu"],
+        display_manager: DisplayManager::None,
+        sessions: &[Session::Wayland],
+        extras: &[("waybar", &["waybar"]), ("mako", &["mako"])],
+    },
+    Profile {
+        name: "hyprland",
+        packages: &["hyprland", "kitty", "xdg-desktop-portal-hyprland"],
+        display_manager: DisplayManager::None,
+        sessions: &[Session::Wayland],
+        extras: &[("waybar", &["waybar"]), ("wofi", &["wofi"])],
+    },
+];
+
+pub fn profile_names() -> Vec<&'static str> {
+    PROFILES.iter().map(|profile| profile.name).collect()
+}
+
+pub fn profile(name: &str) -> Option<&'static Profile> {
+    PROFILES.iter().find(|profile| profile.name == name)
+}
+
+impl Profile {
+    // The [desktop] table with the defaults of the profile
+    pub fn defaults(&self) -> DesktopConfig {
+        let mut desktop = DesktopConfig {
+            profile: self.name.to_string(),
+            display_manager: self.display_manager,
+            session: self.sessions[0],
+            extras: Vec::new(),
+            packages: Vec::new(),
+        };
+        desktop.packages = packages_for(&desktop);
+        desktop
+    }
+
+    pub fn extra_names(&self) -> Vec<String> {
+        self.extras.iter().map(|(name, _)| name.to_string()).collect()
+    }
+}
+
+// Everything to install for the desktop: the bundle, X server, display manager and extras
+pub fn packages_for(desktop: &DesktopConfig) -> Vec<String> {
+    let Some(profile) = profile(&desktop.profile) else {
+        return Vec::new();
+    };
+    let extras = profile
+        .extras
+        .iter()
+        .filter(|(name, _)| desktop.extras.iter().any(|extra| extra == name))
+        .flat_map(|(_, packages)| packages.iter());
+    let all = profile
+        .packages
+        .iter()
+        .chain(desktop.session.packages())
+        .chain(desktop.display_manager.packages())
+        .chain(extras)
+        .map(|package| package.to_string());
+    packages::dedupe(all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_defaults() {
+        let gnome = profile("gnome").unwrap().defaults();
+        assert_eq!(gnome.display_manager, DisplayManager::Gdm);
+        assert_eq!(gnome.session, Session::Wayland);
+        assert_eq!(gnome.packages, vec!["gnome", "gdm"]);
+    }
+
+    #[test]
+    fn packages_follow_the_answers() {
+        let mut i3 = profile("i3").unwrap().defaults();
+        i3.display_manager = DisplayManager::None;
+        i3.extras = vec!["picom".to_string()];
+        assert_eq!(packages_for(&i3), vec!["i3-wm", "i3status", "dmenu", "xterm", "xorg-server", "picom"]);
+    }
+}
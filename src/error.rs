@@ -0,0 +1,45 @@
+// Error type reported to the user once the terminal has been restored
+use std::{error::Error, fmt, io};
+
+// An I/O error along with what the app was doing when it happened
+#[derive(Debug)]
+pub struct AppError {
+    context: String,
+    source: io::Error,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+// Attach context to io results, `terminal.draw(..).context("drawing the UI")?`
+pub trait Context<T> {
+    fn context(self, context: &str) -> Result<T, AppError>;
+}
+
+impl<T> Context<T> for io::Result<T> {
+    fn context(self, context: &str) -> Result<T, AppError> {
+        self.map_err(|source| AppError {
+            context: context.to_string(),
+            source,
+        })
+    }
+}
+
+// Print the error and its chain of causes to stderr
+pub fn report(error: &AppError) {
+    eprintln!("error: {}", error);
+    let mut cause = error.source.source();
+    while let Some(inner) = cause {
+        eprintln!("  caused by: {}", inner);
+        cause = inner.source();
+    }
+}
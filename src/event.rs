@@ -0,0 +1,76 @@
+// Where key presses come from, the terminal in the app and a script in tests
+use crossterm::event::{self, Event};
+#[cfg(test)]
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::{io, time::Duration};
+
+pub trait EventSource {
+    // Wait up to `timeout` for an event, true when one is ready to read
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+    // Block until the next event
+    fn read(&mut self) -> io::Result<Event>;
+}
+
+// Events read from the real terminal
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        event::read()
+    }
+}
+
+// A fixed list of events, reading past the end is an error so a test can't hang
+#[cfg(test)]
+#[derive(Default)]
+pub struct ScriptedEvents {
+    events: VecDeque<Event>,
+}
+
+#[cfg(test)]
+impl ScriptedEvents {
+    pub fn new() -> Self {
+        ScriptedEvents::default()
+    }
+
+    pub fn key(mut self, code: KeyCode) -> Self {
+        self.events.push_back(Event::Key(KeyEvent::from(code)));
+        self
+    }
+
+    pub fn ctrl(mut self, c: char) -> Self {
+        self.events.push_back(Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)));
+        self
+    }
+
+    // One key press per character
+    pub fn text(mut self, text: &str) -> Self {
+        for c in text.chars() {
+            self = self.key(KeyCode::Char(c));
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+impl EventSource for ScriptedEvents {
+    fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+        Ok(!self.events.is_empty())
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "scripted events ran out"))
+    }
+}
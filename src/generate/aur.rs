@@ -0,0 +1,71 @@
+// A script bootstrapping the AUR helper and installing the AUR packages.
+// makepkg won't build as root, so the helper is built by the build user with a
+// sudoers drop-in letting it run pacman for as long as the script runs.
+use super::shell_quote;
+use crate::config::AurConfig;
+
+pub const SCRIPT_PATH: &str = "root/aur-setup.sh";
+
+pub fn setup_script(aur: &AurConfig) -> String {
+    let helper = aur.helper.name();
+    let mut script = format!(
+        r#"#!/bin/bash
+# Build the {helper} AUR helper and install the AUR packages.
+# Run as root on the installed system, it needs the network.
+set -euo pipefail
+
+BUILD_USER={user}
+SUDOERS=/etc/sudoers.d/90-aur-setup
+
+pacman -S --needed --noconfirm base-devel git sudo
+
+# Let the build user install what it builds until the script ends
+echo "$BUILD_USER ALL=(ALL) NOPASSWD: /usr/bin/pacman" > "$SUDOERS"
+chmod 0440 "$SUDOERS"
+trap 'rm -f "$SUDOERS"' EXIT
+
+BUILD_DIR=$(sudo -u "$BUILD_USER" mktemp -d)
+sudo -u "$BUILD_USER" git clone {url} "$BUILD_DIR/{helper}"
+(cd "$BUILD_DIR/{helper}" && sudo -u "$BUILD_USER" makepkg -si --noconfirm)
+rm -rf "$BUILD_DIR"
+"#,
+        helper = helper,
+        user = shell_quote(&aur.build_user),
+        url = aur.helper.repo_url(),
+    );
+    if !aur.packages.is_empty() {
+        let packages: Vec<String> = aur.packages.iter().map(|package| shell_quote(package)).collect();
+        script.push_str(&format!(
+            "\nsudo -u \"$BUILD_USER\" {} -S --needed --noconfirm {}\n",
+            helper,
+            packages.join(" ")
+        ));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::AurHelper, tests::assert_golden};
+
+    #[test]
+    fn paru_with_packages() {
+        let aur = AurConfig {
+            helper: AurHelper::Paru,
+            build_user: "sam".to_string(),
+            packages: vec!["visual-studio-code-bin".to_string(), "spotify".to_string()],
+        };
+        assert_golden("aur-setup-paru.sh", &setup_script(&aur));
+    }
+
+    #[test]
+    fn yay_without_packages() {
+        let aur = AurConfig {
+            helper: AurHelper::Yay,
+            build_user: "sam".to_string(),
+            packages: Vec::new(),
+        };
+        assert_golden("aur-setup-yay.sh", &setup_script(&aur));
+    }
+}
@@ -0,0 +1,332 @@
+// systemd-boot's loader.conf and entries or /etc/default/grub, and a script installing the bootloader.
+// The ESP is mounted at /boot so the kernels sit next to the bootloader.
+// With UKIs the command line goes to /etc/kernel/cmdline and systemd-boot finds the images without entries.
+use super::{fill_placeholders, fstab, mkinitcpio, secure_boot, GeneratedFile};
+use crate::{
+    config::{ArchConfig, GrubConfig, GrubTarget, SystemdBootConfig},
+    kernel,
+};
+
+pub const ESP: &str = "/boot";
+pub const SCRIPT_PATH: &str = "root/bootloader.sh";
+const LOADER_CONF_PATH: &str = "boot/loader/loader.conf";
+const ENTRIES_DIR: &str = "boot/loader/entries";
+const GRUB_DEFAULT_PATH: &str = "etc/default/grub";
+pub const CMDLINE_PATH: &str = "etc/kernel/cmdline";
+const UKI_DIR: &str = "boot/EFI/Linux";
+
+pub fn files(config: &ArchConfig) -> Vec<GeneratedFile> {
+    let systemd_boot = config.systemd_boot.as_ref();
+    let mut files = vec![
+        GeneratedFile {
+            path: LOADER_CONF_PATH.to_string(),
+            mode: 0o644,
+            contents: systemd_boot.map(|systemd_boot| loader_conf(config, systemd_boot)),
+        },
+        GeneratedFile {
+            path: GRUB_DEFAULT_PATH.to_string(),
+            mode: 0o644,
+            contents: config.grub.as_ref().map(|grub| grub_default(config, grub)),
+        },
+        GeneratedFile {
+            path: CMDLINE_PATH.to_string(),
+            mode: 0o644,
+            contents: uki(config).then(|| kernel::cmdline(config).join(" ") + "\n"),
+        },
+        GeneratedFile {
+            path: SCRIPT_PATH.to_string(),
+            mode: 0o700,
+            contents: script(config),
+        },
+    ];
+    // One entry per kernel on offer so dropping a kernel removes its entry
+    for name in kernel::kernel_names() {
+        let picked = systemd_boot.is_some() && !uki(config) && config.kernel.kernels.iter().any(|kernel| kernel == name);
+        files.push(GeneratedFile {
+            path: entry_path(name),
+            mode: 0o644,
+            contents: picked.then(|| entry(config, name)),
+        });
+    }
+    files
+}
+
+// An EFI install mounts the ESP at /boot
+pub fn uses_esp(config: &ArchConfig) -> bool {
+    config.systemd_boot.is_some() || config.grub.as_ref().is_some_and(|grub| grub.target == GrubTarget::Efi)
+}
+
+// UKIs only make sense with systemd-boot here, GRUB would need them chainloaded
+pub fn uki(config: &ArchConfig) -> bool {
+    config.kernel.uki && config.systemd_boot.is_some()
+}
+
+// Where mkinitcpio writes the image for a kernel, relative to the target root
+pub fn uki_path(kernel: &str) -> String {
+    format!("{}/arch-{}.efi", UKI_DIR, kernel)
+}
+
+fn entry_name(kernel: &str) -> String {
+    format!("arch-{}.conf", kernel)
+}
+
+fn entry_path(kernel: &str) -> String {
+    format!("{}/{}", ENTRIES_DIR, entry_name(kernel))
+}
+
+pub fn loader_conf(config: &ArchConfig, systemd_boot: &SystemdBootConfig) -> String {
+    format!(
+        "default {}\ntimeout {}\nconsole-mode max\neditor {}\n",
+        match uki(config) {
+            true => format!("arch-{}.efi", config.kernel.default),
+            false => entry_name(&config.kernel.default),
+        },
+        systemd_boot.timeout,
+        if systemd_boot.editor { "yes" } else { "no" }
+    )
+}
+
+pub fn entry(config: &ArchConfig, kernel: &str) -> String {
+    format!(
+        "title   Arch Linux ({})\nlinux   /vmlinuz-{}\ninitrd  /initramfs-{}.img\noptions {}\n",
+        kernel,
+        kernel,
+        kernel,
+        kernel::cmdline(config).join(" ")
+    )
+}
+
+// grub-mkconfig adds the root device itself, quiet goes in _DEFAULT so the recovery entries stay verbose
+pub fn grub_default(config: &ArchConfig, grub: &GrubConfig) -> String {
+    let mut linux = kernel::luks_params(config);
+    linux.extend(super::swap::resume_params(&config.swap));
+    linux.extend(kernel::extra_params(config));
+    let mut lines = vec![
+        "# From the [grub] answers, run grub-mkconfig -o /boot/grub/grub.cfg after changing it".to_string(),
+        "GRUB_DEFAULT=0".to_string(),
+        format!("GRUB_TIMEOUT={}", grub.timeout),
+        "GRUB_DISTRIBUTOR=\"Arch\"".to_string(),
+        format!("GRUB_CMDLINE_LINUX_DEFAULT=\"{}\"", kernel::console_params(config).join(" ")),
+        format!("GRUB_CMDLINE_LINUX=\"{}\"", linux.join(" ")),
+        "GRUB_PRELOAD_MODULES=\"part_gpt part_msdos\"".to_string(),
+        "GRUB_TIMEOUT_STYLE=menu".to_string(),
+        "GRUB_TERMINAL_INPUT=console".to_string(),
+        "GRUB_GFXMODE=auto".to_string(),
+        "GRUB_GFXPAYLOAD_LINUX=keep".to_string(),
+        "GRUB_DISABLE_RECOVERY=true".to_string(),
+    ];
+    // Without an ESP /boot is inside the LUKS root, GRUB unlocks it before reading anything (theme included)
+    if config.storage.encrypt && !uses_esp(config) {
+        lines.push("GRUB_ENABLE_CRYPTODISK=y".to_string());
+    }
+    if grub.os_prober {
+        lines.push("GRUB_DISABLE_OS_PROBER=false".to_string());
+    }
+    if !grub.theme.is_empty() {
+        lines.push(format!("GRUB_THEME=\"/usr/share/grub/themes/{}/theme.txt\"", grub.theme));
+    }
+    // The default kernel gets the top entry, the others go under Advanced options
+    if config.kernel.kernels.len() > 1 {
+        lines.push(format!("GRUB_TOP_LEVEL=\"{}/vmlinuz-{}\"", ESP, config.kernel.default));
+    }
+    lines.join("\n") + "\n"
+}
+
+// Run inside arch-chroot once the disks are mounted, fills in the placeholders first
+pub fn script(config: &ArchConfig) -> Option<String> {
+    // Every file with placeholders in it
+    let mut contents = fstab::fstab(config);
+    let mut paths = vec![format!("/{}", fstab::FSTAB_PATH)];
+    let mut steps = Vec::new();
+    if let Some(grub) = &config.grub {
+        contents.push_str(&grub_default(config, grub));
+        paths.push(format!("/{}", GRUB_DEFAULT_PATH));
+        let mut packages = vec!["grub"];
+        if grub.target == GrubTarget::Efi {
+            packages.push("efibootmgr");
+        }
+        if grub.os_prober {
+            packages.push("os-prober");
+        }
+        steps.push(format!("pacman -S --needed --noconfirm {}", packages.join(" ")));
+        match grub.target {
+            // sbctl signs GRUB itself, without shim
+            GrubTarget::Efi if config.secure_boot.is_some() => steps.push(format!(
+                "grub-install --target=x86_64-efi --efi-directory={} --bootloader-id=GRUB --modules=\"tpm\" --disable-shim-lock",
+                ESP
+            )),
+            GrubTarget::Efi => steps.push(format!(
+                "grub-install --target=x86_64-efi --efi-directory={} --bootloader-id=GRUB",
+                ESP
+            )),
+            GrubTarget::Bios => {
+                // The disk holding the root filesystem, through any LUKS mapping
+                let source = "\"$(findmnt -nvo SOURCE /)\"";
+                steps.push(format!("disk=$(lsblk -lnspo NAME,TYPE {} | awk '$2 == \"disk\" {{ print $1; exit }}')", source));
+                steps.push("grub-install --target=i386-pc \"$disk\"".to_string());
+            }
+        }
+        steps.push(format!("grub-mkconfig -o {}/grub/grub.cfg", ESP));
+    } else if config.systemd_boot.is_some() {
+        if uki(config) {
+            contents.push_str(&kernel::cmdline(config).join(" "));
+            paths.push(format!("/{}", CMDLINE_PATH));
+        } else {
+            for kernel in &config.kernel.kernels {
+                contents.push_str(&entry(config, kernel));
+                paths.push(format!("/{}", entry_path(kernel)));
+            }
+        }
+        steps.push("bootctl install".to_string());
+    } else {
+        return None;
+    }
+    let mut script = fill_placeholders(config, &contents, &paths);
+    script.extend(steps);
+    // The drop-in's hooks and modules fail the build without their packages, services.sh runs later
+    let packages = mkinitcpio::packages(config);
+    if !packages.is_empty() {
+        script.push(format!("pacman -S --needed --noconfirm {}", packages.join(" ")));
+    }
+    // The images again, with the mkinitcpio drop-in and a UKI's command line in place
+    script.push("mkinitcpio -P".to_string());
+    script.extend(secure_boot::steps(config));
+    Some(format!(
+        "#!/bin/bash\n# Install the bootloader, run inside arch-chroot.\nset -euo pipefail\n\n{}\n",
+        script.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{BootMessages, Gpu, KernelConfig, SecureBootConfig, StorageConfig, SwapConfig, SwapKind},
+        tests::assert_golden,
+    };
+
+    fn config() -> ArchConfig {
+        ArchConfig {
+            format_type: "btrfs".to_string(),
+            kernel: KernelConfig {
+                kernels: vec!["linux".to_string(), "linux-lts".to_string()],
+                default: "linux-lts".to_string(),
+                ..KernelConfig::default()
+            },
+            ..ArchConfig::default()
+        }
+    }
+
+    #[test]
+    fn systemd_boot() {
+        let config = ArchConfig {
+            bootloader: "systemd-boot".to_string(),
+            systemd_boot: Some(SystemdBootConfig::default()),
+            swap: SwapConfig {
+                kind: SwapKind::Swapfile,
+                hibernate: true,
+                ..SwapConfig::default()
+            },
+            ..config()
+        };
+        let files = files(&config);
+        let contents = |path: &str| files.iter().find(|file| file.path == path).unwrap().contents.clone();
+        assert_golden("loader.conf", &contents(LOADER_CONF_PATH).unwrap());
+        assert_golden("arch-linux-lts.conf", &contents("boot/loader/entries/arch-linux-lts.conf").unwrap());
+        assert_golden("bootloader-systemd-boot.sh", &contents(SCRIPT_PATH).unwrap());
+        assert_eq!(contents("boot/loader/entries/arch-linux-zen.conf"), None);
+        assert_eq!(contents(GRUB_DEFAULT_PATH), None);
+    }
+
+    #[test]
+    fn signed_ukis() {
+        let mut config = ArchConfig {
+            systemd_boot: Some(SystemdBootConfig::default()),
+            secure_boot: Some(SecureBootConfig::default()),
+            ..config()
+        };
+        config.kernel.uki = true;
+        let files = files(&config);
+        let contents = |path: &str| files.iter().find(|file| file.path == path).unwrap().contents.clone();
+        assert_eq!(contents(LOADER_CONF_PATH).unwrap().lines().next(), Some("default arch-linux-lts.efi"));
+        assert_eq!(contents("boot/loader/entries/arch-linux.conf"), None);
+        assert_eq!(contents(CMDLINE_PATH).unwrap(), "root=UUID=@ROOT_UUID@ rw rootflags=subvol=@ quiet loglevel=3\n");
+        assert_golden("bootloader-systemd-boot-uki.sh", &contents(SCRIPT_PATH).unwrap());
+    }
+
+    #[test]
+    fn grub_efi() {
+        let config = ArchConfig {
+            bootloader: "grub".to_string(),
+            grub: Some(GrubConfig {
+                os_prober: true,
+                theme: "starfield".to_string(),
+                ..GrubConfig::default()
+            }),
+            storage: StorageConfig {
+                encrypt: true,
+                ..StorageConfig::default()
+            },
+            ..config()
+        };
+        assert_golden("grub-default", &grub_default(&config, config.grub.as_ref().unwrap()));
+        assert_golden("bootloader-grub-efi.sh", &script(&config).unwrap());
+    }
+
+    #[test]
+    fn grub_bios() {
+        let config = ArchConfig {
+            bootloader: "grub".to_string(),
+            grub: Some(GrubConfig {
+                target: GrubTarget::Bios,
+                ..GrubConfig::default()
+            }),
+            ..ArchConfig::default()
+        };
+        assert_golden("bootloader-grub-bios.sh", &script(&config).unwrap());
+    }
+
+    #[test]
+    fn grub_bios_encrypted() {
+        let config = ArchConfig {
+            bootloader: "grub".to_string(),
+            grub: Some(GrubConfig {
+                target: GrubTarget::Bios,
+                theme: "starfield".to_string(),
+                ..GrubConfig::default()
+            }),
+            storage: StorageConfig {
+                encrypt: true,
+                ..StorageConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_golden("grub-default-bios-encrypted", &grub_default(&config, config.grub.as_ref().unwrap()));
+    }
+
+    #[test]
+    fn plymouth_installed_before_the_images() {
+        let mut config = ArchConfig {
+            systemd_boot: Some(SystemdBootConfig::default()),
+            ..config()
+        };
+        config.kernel.boot_messages = BootMessages::Splash;
+        let script = script(&config).unwrap();
+        let install = script.find("pacman -S --needed --noconfirm plymouth").expect("plymouth is installed");
+        assert!(install < script.find("mkinitcpio -P").unwrap());
+    }
+
+    #[test]
+    fn nvidia_driver_installed_before_the_images() {
+        let mut config = ArchConfig {
+            systemd_boot: Some(SystemdBootConfig::default()),
+            ..config()
+        };
+        config.kernel.gpu = Gpu::Nvidia;
+        let script = script(&config).unwrap();
+        let install = script
+            .find("pacman -S --needed --noconfirm nvidia-dkms linux-headers linux-lts-headers")
+            .expect("the driver and headers are installed");
+        assert!(install < script.find("mkinitcpio -P").unwrap());
+    }
+}
@@ -0,0 +1,179 @@
+// The future /etc/fstab, worked out from the answers instead of genfstab.
+// UUIDs are placeholders until the disks are formatted. root/bootloader.sh fills them in, so it
+// has to run before the first boot even when the bootloader is installed some other way.
+use super::{
+    bootloader::{self, ESP},
+    swap, EFI_UUID, ROOT_UUID, SWAP_UUID,
+};
+use crate::config::{ArchConfig, SwapKind};
+
+pub const FSTAB_PATH: &str = "etc/fstab";
+
+// btrfs subvolumes and where they are mounted, snapshots of @ leave the others out
+pub const SUBVOLUMES: [(&str, &str); 5] = [
+    ("@", "/"),
+    ("@home", "/home"),
+    ("@log", "/var/log"),
+    ("@pkg", "/var/cache/pacman/pkg"),
+    ("@snapshots", "/.snapshots"),
+];
+
+// One line of fstab
+pub struct Entry {
+    pub device: String,
+    pub mount: String,
+    pub fs_type: &'static str,
+    pub options: String,
+    pub pass: u8,
+}
+
+pub fn entries(config: &ArchConfig) -> Vec<Entry> {
+    let root = format!("UUID={}", ROOT_UUID);
+    let mut entries = Vec::new();
+    match config.format_type.as_str() {
+        "btrfs" => {
+            let compress = match config.storage.btrfs_compression_level {
+                Some(level) => format!("zstd:{}", level),
+                None => "zstd".to_string(),
+            };
+            for (subvolume, mount) in SUBVOLUMES {
+                entries.push(Entry {
+                    device: root.clone(),
+                    mount: mount.to_string(),
+                    fs_type: "btrfs",
+                    options: format!("rw,noatime,compress={},space_cache=v2,subvol=/{}", compress, subvolume),
+                    pass: 0,
+                });
+            }
+        }
+        // xfs checks itself when mounting, fsck.xfs does nothing
+        "xfs" => entries.push(Entry {
+            device: root,
+            mount: "/".to_string(),
+            fs_type: "xfs",
+            options: "rw,relatime,attr2,inode64,logbufs=8,logbsize=32k,noquota".to_string(),
+            pass: 0,
+        }),
+        _ => entries.push(Entry {
+            device: root,
+            mount: "/".to_string(),
+            fs_type: "ext4",
+            options: "rw,relatime".to_string(),
+            pass: 1,
+        }),
+    }
+    if super::bootloader::uses_esp(config) {
+        entries.push(Entry {
+            device: format!("UUID={}", EFI_UUID),
+            mount: ESP.to_string(),
+            fs_type: "vfat",
+            // Only root reads the ESP, the random seed lives there
+            options: "rw,relatime,fmask=0077,dmask=0077,codepage=437,iocharset=ascii,shortname=mixed,utf8,errors=remount-ro"
+                .to_string(),
+            pass: 2,
+        });
+    }
+    // zram-generator sets up its own device
+    match config.swap.kind {
+        SwapKind::Partition => entries.push(swap_entry(format!("UUID={}", SWAP_UUID))),
+        SwapKind::Swapfile => entries.push(swap_entry(swap::swapfile_path(&config.format_type).to_string())),
+        SwapKind::Zram | SwapKind::None => {}
+    }
+    entries
+}
+
+fn swap_entry(device: String) -> Entry {
+    Entry {
+        device,
+        mount: "none".to_string(),
+        fs_type: "swap",
+        options: "defaults".to_string(),
+        pass: 0,
+    }
+}
+
+pub fn fstab(config: &ArchConfig) -> String {
+    let mut fstab = format!(
+        "# <file system> <dir> <type> <options> <dump> <pass>\n# The @...@ UUIDs are filled in by /{}\n",
+        bootloader::SCRIPT_PATH
+    );
+    if config.format_type == "btrfs" {
+        let names: Vec<&str> = SUBVOLUMES.iter().map(|(subvolume, _)| *subvolume).collect();
+        fstab.push_str(&format!("# The subvolumes {} are made before mounting\n", names.join(", ")));
+    }
+    for entry in entries(config) {
+        fstab.push_str(&format!(
+            "\n{}\t{}\t{}\t{}\t0 {}\n",
+            entry.device, entry.mount, entry.fs_type, entry.options, entry.pass
+        ));
+    }
+    fstab
+}
+
+// The mounts at the end of the review, `fstab./home = UUID=... btrfs ...`
+pub fn review_lines(config: &ArchConfig) -> Vec<(String, String)> {
+    entries(config)
+        .into_iter()
+        .map(|entry| {
+            let key = match entry.fs_type {
+                "swap" => "fstab.swap".to_string(),
+                _ => format!("fstab.{}", entry.mount),
+            };
+            (key, format!("{} {} {}", entry.device, entry.fs_type, entry.options))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{StorageConfig, SwapConfig, SystemdBootConfig},
+        tests::assert_golden,
+    };
+
+    fn config(format_type: &str, kind: SwapKind) -> ArchConfig {
+        ArchConfig {
+            format_type: format_type.to_string(),
+            systemd_boot: Some(SystemdBootConfig::default()),
+            swap: SwapConfig {
+                kind,
+                ..SwapConfig::default()
+            },
+            ..ArchConfig::default()
+        }
+    }
+
+    #[test]
+    fn ext4_with_a_swap_partition() {
+        assert_golden("fstab-ext4", &fstab(&config("ext4", SwapKind::Partition)));
+    }
+
+    #[test]
+    fn btrfs_subvolumes_and_swapfile() {
+        let config = ArchConfig {
+            storage: StorageConfig {
+                btrfs_compression_level: Some(3),
+                ..StorageConfig::default()
+            },
+            ..config("btrfs", SwapKind::Swapfile)
+        };
+        assert_golden("fstab-btrfs", &fstab(&config));
+    }
+
+    #[test]
+    fn xfs_with_zram() {
+        assert_golden("fstab-xfs", &fstab(&config("xfs", SwapKind::Zram)));
+    }
+
+    #[test]
+    fn no_esp_for_bios() {
+        let config = ArchConfig {
+            systemd_boot: None,
+            ..config("ext4", SwapKind::None)
+        };
+        let review = review_lines(&config);
+        assert_eq!(review.len(), 1);
+        assert_eq!(review[0], ("fstab./".to_string(), "UUID=@ROOT_UUID@ ext4 rw,relatime".to_string()));
+    }
+}
@@ -0,0 +1,185 @@
+// The mkinitcpio HOOKS and MODULES the answers need, as a drop-in read after /etc/mkinitcpio.conf,
+// and presets building unified kernel images when they are wanted.
+use super::{
+    bootloader::{self, ESP},
+    GeneratedFile,
+};
+use crate::{
+    config::{ArchConfig, BootMessages, Gpu, Initramfs, SwapKind},
+    kernel,
+};
+
+const DROP_IN_PATH: &str = "etc/mkinitcpio.conf.d/10-arch-menu.conf";
+const PRESET_DIR: &str = "etc/mkinitcpio.d";
+const NVIDIA_MODULES: [&str; 4] = ["nvidia", "nvidia_modeset", "nvidia_uvm", "nvidia_drm"];
+
+pub fn files(config: &ArchConfig) -> Vec<GeneratedFile> {
+    let mut files = vec![GeneratedFile {
+        path: DROP_IN_PATH.to_string(),
+        mode: 0o644,
+        contents: Some(drop_in(config)),
+    }];
+    // The packages' own presets come back when UKIs are turned off
+    for name in kernel::kernel_names() {
+        let picked = bootloader::uki(config) && config.kernel.kernels.iter().any(|kernel| kernel == name);
+        files.push(GeneratedFile {
+            path: format!("{}/{}.preset", PRESET_DIR, name),
+            mode: 0o644,
+            contents: picked.then(|| uki_preset(name)),
+        });
+    }
+    files
+}
+
+pub fn hooks(config: &ArchConfig) -> Vec<&'static str> {
+    let systemd = config.kernel.initramfs == Initramfs::Systemd;
+    let mut hooks = vec!["base", if systemd { "systemd" } else { "udev" }];
+    // Before anything asks for the passphrase
+    if config.kernel.boot_messages == BootMessages::Splash {
+        hooks.push("plymouth");
+    }
+    hooks.extend(["autodetect", "microcode", "modconf"]);
+    // The NVIDIA modules are loaded early instead, kms would bring in nouveau
+    if config.kernel.gpu != Gpu::Nvidia {
+        hooks.push("kms");
+    }
+    hooks.push("keyboard");
+    hooks.extend(if systemd { &["sd-vconsole"][..] } else { &["keymap", "consolefont"][..] });
+    hooks.push("block");
+    if config.storage.encrypt {
+        hooks.push(if systemd { "sd-encrypt" } else { "encrypt" });
+    }
+    // systemd resumes by itself
+    if !systemd && config.swap.hibernate && config.swap.kind != SwapKind::Zram {
+        hooks.push("resume");
+    }
+    hooks.push("filesystems");
+    // btrfs has no fsck to run at boot
+    if config.format_type != "btrfs" {
+        hooks.push("fsck");
+    }
+    hooks
+}
+
+pub fn modules(config: &ArchConfig) -> Vec<&'static str> {
+    let mut modules = Vec::new();
+    if config.format_type == "btrfs" {
+        modules.push("btrfs");
+    }
+    if config.kernel.gpu == Gpu::Nvidia {
+        modules.extend(NVIDIA_MODULES);
+    }
+    modules
+}
+
+// Packages the hooks and modules come from, missing ones fail `mkinitcpio -P`
+pub fn packages(config: &ArchConfig) -> Vec<String> {
+    let mut packages = Vec::new();
+    if config.kernel.boot_messages == BootMessages::Splash {
+        packages.push("plymouth".to_string());
+    }
+    // The DKMS build covers every kernel on offer, given its headers
+    if config.kernel.gpu == Gpu::Nvidia {
+        packages.push("nvidia-dkms".to_string());
+        packages.extend(config.kernel.kernels.iter().map(|kernel| format!("{}-headers", kernel)));
+    }
+    packages
+}
+
+pub fn drop_in(config: &ArchConfig) -> String {
+    format!(
+        "# From the storage, swap and kernel answers, read after /etc/mkinitcpio.conf\nMODULES=({})\nHOOKS=({})\n",
+        modules(config).join(" "),
+        hooks(config).join(" ")
+    )
+}
+
+// Replaces the package's preset, the command line comes from /etc/kernel/cmdline
+pub fn uki_preset(kernel: &str) -> String {
+    format!(
+        "# mkinitcpio preset for '{}', built into a unified kernel image\nALL_kver=\"{}/vmlinuz-{}\"\n\nPRESETS=('default')\n\ndefault_uki=\"/{}\"\n",
+        kernel,
+        ESP,
+        kernel,
+        bootloader::uki_path(kernel)
+    )
+}
+
+// Shown at the end of the review, they aren't answers but follow from them
+pub fn review_lines(config: &ArchConfig) -> Vec<(String, String)> {
+    vec![
+        ("mkinitcpio.hooks".to_string(), hooks(config).join(" ")),
+        ("mkinitcpio.modules".to_string(), modules(config).join(" ")),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{KernelConfig, StorageConfig, SwapConfig, SystemdBootConfig},
+        tests::assert_golden,
+    };
+
+    #[test]
+    fn plain_ext4() {
+        let config = ArchConfig {
+            format_type: "ext4".to_string(),
+            ..ArchConfig::default()
+        };
+        assert_golden("mkinitcpio-ext4.conf", &drop_in(&config));
+    }
+
+    #[test]
+    fn encrypted_btrfs_with_nvidia_and_plymouth() {
+        let config = ArchConfig {
+            format_type: "btrfs".to_string(),
+            storage: StorageConfig {
+                encrypt: true,
+                ..StorageConfig::default()
+            },
+            kernel: KernelConfig {
+                boot_messages: BootMessages::Splash,
+                gpu: Gpu::Nvidia,
+                ..KernelConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_golden("mkinitcpio-btrfs-luks-nvidia.conf", &drop_in(&config));
+    }
+
+    #[test]
+    fn busybox_resumes_with_its_hook() {
+        let config = ArchConfig {
+            format_type: "xfs".to_string(),
+            storage: StorageConfig {
+                encrypt: true,
+                ..StorageConfig::default()
+            },
+            swap: SwapConfig {
+                kind: SwapKind::Partition,
+                hibernate: true,
+                ..SwapConfig::default()
+            },
+            kernel: KernelConfig {
+                initramfs: Initramfs::Busybox,
+                ..KernelConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_golden("mkinitcpio-busybox.conf", &drop_in(&config));
+    }
+
+    #[test]
+    fn uki_presets_per_kernel() {
+        let mut config = ArchConfig {
+            systemd_boot: Some(SystemdBootConfig::default()),
+            ..ArchConfig::default()
+        };
+        let presets = |config: &ArchConfig| files(config).into_iter().filter_map(|file| file.contents.map(|_| file.path));
+        assert_eq!(presets(&config).count(), 1);
+        config.kernel.uki = true;
+        assert_eq!(presets(&config).collect::<Vec<_>>(), vec![DROP_IN_PATH, "etc/mkinitcpio.d/linux.preset"]);
+        assert_golden("linux.preset", &uki_preset("linux"));
+    }
+}
@@ -0,0 +1,248 @@
+// Files worked out from the answers for the install script to copy into the new system.
+// They are written below a directory next to the config, at their path on the target
+// (`etc/...`), so installing them is a copy of the whole tree into /mnt.
+pub mod aur;
+pub mod bootloader;
+pub mod fstab;
+pub mod mkinitcpio;
+pub mod network;
+pub mod reflector;
+pub mod secure_boot;
+pub mod services;
+pub mod ssh;
+pub mod swap;
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use crate::config::ArchConfig;
+
+// Stand-ins for what is only known once the disk is partitioned and formatted
+pub const ROOT_UUID: &str = "@ROOT_UUID@";
+pub const SWAP_UUID: &str = "@SWAP_UUID@";
+pub const RESUME_OFFSET: &str = "@RESUME_OFFSET@";
+pub const LUKS_UUID: &str = "@LUKS_UUID@";
+pub const EFI_UUID: &str = "@EFI_UUID@";
+
+// Shell lines filling in the placeholders found in `contents` with what the installed system has, in `paths`
+pub fn fill_placeholders(config: &ArchConfig, contents: &str, paths: &[String]) -> Vec<String> {
+    let values = [
+        (ROOT_UUID, "findmnt -no UUID /".to_string()),
+        (EFI_UUID, format!("findmnt -no UUID {}", bootloader::ESP)),
+        (LUKS_UUID, "blkid -t TYPE=crypto_LUKS -s UUID -o value | head -n 1".to_string()),
+        (SWAP_UUID, "blkid -t TYPE=swap -s UUID -o value | head -n 1".to_string()),
+        (RESUME_OFFSET, swap::offset_command(&config.format_type)),
+    ];
+    let mut lines = Vec::new();
+    let mut edits = Vec::new();
+    for (placeholder, command) in values.iter().filter(|(placeholder, _)| contents.contains(placeholder)) {
+        let variable = placeholder.trim_matches('@').to_lowercase();
+        lines.push(format!("{}=$({})", variable, command));
+        edits.push(format!("-e \"s|{}|${}|g\"", placeholder, variable));
+    }
+    if !edits.is_empty() {
+        lines.push(format!("sed -i {} {}", edits.join(" "), paths.join(" ")));
+    }
+    lines
+}
+
+// One file on the target system
+#[derive(Debug, PartialEq)]
+pub struct GeneratedFile {
+    // Relative to the target root
+    pub path: String,
+    pub mode: u32,
+    // None when the answers don't call for the file, a stale copy is removed
+    pub contents: Option<String>,
+}
+
+// Every file the answers can produce
+pub fn files(config: &ArchConfig) -> Vec<GeneratedFile> {
+    let mut files = vec![
+        GeneratedFile {
+            path: aur::SCRIPT_PATH.to_string(),
+            mode: 0o700,
+            contents: config.aur.as_ref().map(aur::setup_script),
+        },
+        GeneratedFile {
+            path: reflector::CONF_PATH.to_string(),
+            mode: 0o644,
+            contents: Some(reflector::conf(&config.mirrors)),
+        },
+        GeneratedFile {
+            path: reflector::MIRRORLIST_PATH.to_string(),
+            mode: 0o644,
+            contents: reflector::mirrorlist(&config.mirrors),
+        },
+        GeneratedFile {
+            path: services::SCRIPT_PATH.to_string(),
+            mode: 0o700,
+            contents: Some(services::script(config)),
+        },
+        GeneratedFile {
+            path: fstab::FSTAB_PATH.to_string(),
+            mode: 0o644,
+            contents: Some(fstab::fstab(config)),
+        },
+        GeneratedFile {
+            path: swap::ZRAM_CONF_PATH.to_string(),
+            mode: 0o644,
+            contents: swap::zram_conf(&config.swap),
+        },
+        GeneratedFile {
+            path: swap::SCRIPT_PATH.to_string(),
+            mode: 0o700,
+            contents: swap::swapfile_script(config),
+        },
+    ];
+    files.extend(network::files(&config.network));
+    files.extend(ssh::files(config));
+    files.extend(bootloader::files(config));
+    files.extend(mkinitcpio::files(config));
+    files
+}
+
+// The directory next to the config the files go to, `arch_config_files`
+pub fn output_dir(config_path: &Path) -> PathBuf {
+    let stem = config_path.file_stem().unwrap_or_default().to_string_lossy();
+    config_path.with_file_name(format!("{}_files", stem))
+}
+
+// Directories only the generators write to, named after answers that can change (user names, SSIDs),
+// so anything in them that isn't generated any more is removed
+const OWNED_DIRS: [&str; 2] = [ssh::AUTHORIZED_KEYS_DIR, network::IWD_NETWORKS];
+
+// Write the files below `dir`, removing the ones no longer wanted
+pub fn write_files(config: &ArchConfig, dir: &Path) -> io::Result<()> {
+    let files = files(config);
+    for owned in OWNED_DIRS {
+        let entries = match fs::read_dir(dir.join(owned)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if !files.iter().any(|file| file.contents.is_some() && dir.join(&file.path) == path) {
+                fs::remove_file(&path)?;
+                log_info!("removed stale {}", path.display());
+            }
+        }
+    }
+    for file in files {
+        let path = dir.join(&file.path);
+        match file.contents {
+            Some(contents) => {
+                fs::create_dir_all(path.parent().unwrap_or(dir))?;
+                // Secrets are never readable by others, not even until the chmod of a file that was there
+                let mut out = OpenOptions::new().write(true).create(true).truncate(true).mode(file.mode).open(&path)?;
+                out.set_permissions(fs::Permissions::from_mode(file.mode))?;
+                out.write_all(contents.as_bytes())?;
+                log_info!("wrote {}", path.display());
+            }
+            None => match fs::remove_file(&path) {
+                Ok(()) => log_info!("removed stale {}", path.display()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            },
+        }
+    }
+    Ok(())
+}
+
+// Quote a value for a shell script
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{AurConfig, AurHelper, NetworkBackend, SshConfig, WifiConfig},
+        ssh::tests::ED25519,
+        tests::temp_dir,
+    };
+
+    #[test]
+    fn files_go_next_to_the_config() {
+        assert_eq!(output_dir(Path::new("/tmp/arch_config.toml")), PathBuf::from("/tmp/arch_config_files"));
+        assert_eq!(output_dir(Path::new("mine.toml")), PathBuf::from("mine_files"));
+    }
+
+    #[test]
+    fn unwanted_files_are_removed() {
+        let dir = temp_dir("generate");
+        let mut config = ArchConfig {
+            aur: Some(AurConfig {
+                helper: AurHelper::Yay,
+                build_user: "sam".to_string(),
+                packages: Vec::new(),
+            }),
+            ..ArchConfig::default()
+        };
+        write_files(&config, &dir).unwrap();
+        let script = dir.join(aur::SCRIPT_PATH);
+        assert_eq!(fs::metadata(&script).unwrap().permissions().mode() & 0o777, 0o700);
+
+        config.aur = None;
+        write_files(&config, &dir).unwrap();
+        assert!(!script.exists());
+    }
+
+    #[test]
+    fn keys_of_renamed_users_are_removed() {
+        let dir = temp_dir("generate-keys");
+        let mut config = ArchConfig {
+            username: "sam".to_string(),
+            ssh: Some(SshConfig {
+                authorized_keys: vec![ED25519.to_string()],
+                ..SshConfig::default()
+            }),
+            ..ArchConfig::default()
+        };
+        write_files(&config, &dir).unwrap();
+        assert!(dir.join("etc/ssh/authorized_keys/sam").exists());
+
+        config.username = "alex".to_string();
+        write_files(&config, &dir).unwrap();
+        assert!(!dir.join("etc/ssh/authorized_keys/sam").exists());
+        assert!(dir.join("etc/ssh/authorized_keys/alex").exists());
+
+        config.ssh = None;
+        write_files(&config, &dir).unwrap();
+        assert!(!dir.join("etc/ssh/authorized_keys/alex").exists());
+    }
+
+    #[test]
+    fn secrets_are_private_and_old_networks_removed() {
+        let dir = temp_dir("generate-iwd");
+        let mut config = ArchConfig::default();
+        config.network.backend = NetworkBackend::Iwd;
+        config.network.wifi = Some(WifiConfig {
+            ssid: "Home".to_string(),
+            passphrase: "hunter22".to_string(),
+        });
+        let home = dir.join("var/lib/iwd/Home.psk");
+        fs::create_dir_all(home.parent().unwrap()).unwrap();
+        fs::write(&home, "").unwrap();
+        fs::set_permissions(&home, fs::Permissions::from_mode(0o644)).unwrap();
+        write_files(&config, &dir).unwrap();
+        assert_eq!(fs::metadata(&home).unwrap().permissions().mode() & 0o777, 0o600);
+
+        config.network.wifi.as_mut().unwrap().ssid = "Office".to_string();
+        write_files(&config, &dir).unwrap();
+        assert!(!home.exists());
+        assert!(dir.join("var/lib/iwd/Office.psk").exists());
+    }
+
+    #[test]
+    fn quotes_for_the_shell() {
+        assert_eq!(shell_quote("sam"), "'sam'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
@@ -0,0 +1,240 @@
+// Config files, packages and units for the [network] answers
+use super::GeneratedFile;
+use crate::{
+    config::{NetworkBackend, NetworkConfig, WifiConfig},
+    network,
+};
+
+const NM_WIRED_PATH: &str = "etc/NetworkManager/system-connections/wired.nmconnection";
+const NM_WIFI_PATH: &str = "etc/NetworkManager/system-connections/wifi.nmconnection";
+const NETWORKD_PATH: &str = "etc/systemd/network/20-arch-menu.network";
+const IWD_CONF_PATH: &str = "etc/iwd/main.conf";
+pub const IWD_NETWORKS: &str = "var/lib/iwd";
+// arch-chroot bind mounts /etc/resolv.conf, so the link to the resolved stub is made at boot
+const RESOLV_TMPFILES_PATH: &str = "etc/tmpfiles.d/resolv-stub.conf";
+
+// Every file of every backend, the ones of the other backends as None so they are removed
+pub fn files(network: &NetworkConfig) -> Vec<GeneratedFile> {
+    let backend = network.backend;
+    let wifi = network.wifi.as_ref();
+    let mut files = vec![
+        GeneratedFile {
+            path: NM_WIRED_PATH.to_string(),
+            mode: 0o600,
+            contents: (backend == NetworkBackend::NetworkManager && wifi.is_none() && !network.dhcp)
+                .then(|| nm_connection(network, None)),
+        },
+        GeneratedFile {
+            path: NM_WIFI_PATH.to_string(),
+            mode: 0o600,
+            contents: wifi
+                .filter(|_| backend == NetworkBackend::NetworkManager)
+                .map(|wifi| nm_connection(network, Some(wifi))),
+        },
+        GeneratedFile {
+            path: NETWORKD_PATH.to_string(),
+            mode: 0o644,
+            contents: (backend == NetworkBackend::Networkd).then(|| networkd_file(network)),
+        },
+        GeneratedFile {
+            path: IWD_CONF_PATH.to_string(),
+            mode: 0o644,
+            contents: (backend == NetworkBackend::Iwd).then(iwd_main_conf),
+        },
+        GeneratedFile {
+            path: RESOLV_TMPFILES_PATH.to_string(),
+            mode: 0o644,
+            contents: uses_resolved(network)
+                .then(|| "L+ /etc/resolv.conf - - - - ../run/systemd/resolve/stub-resolv.conf\n".to_string()),
+        },
+    ];
+    // networkd leaves Wi-Fi to iwd too, without its own address handling
+    if let Some(wifi) = wifi.filter(|_| backend != NetworkBackend::NetworkManager) {
+        files.push(GeneratedFile {
+            path: format!("{}/{}", IWD_NETWORKS, network::iwd_file_name(&wifi.ssid, !wifi.passphrase.is_empty())),
+            mode: 0o600,
+            contents: Some(iwd_network(network, wifi)),
+        });
+    }
+    files
+}
+
+pub fn packages(network: &NetworkConfig) -> Vec<&'static str> {
+    match network.backend {
+        NetworkBackend::NetworkManager => vec!["networkmanager"],
+        NetworkBackend::Networkd if network.wifi.is_none() => Vec::new(),
+        NetworkBackend::Networkd | NetworkBackend::Iwd => vec!["iwd"],
+    }
+}
+
+pub fn units(network: &NetworkConfig) -> Vec<&'static str> {
+    match network.backend {
+        NetworkBackend::NetworkManager => vec!["NetworkManager.service"],
+        NetworkBackend::Networkd if network.wifi.is_none() => {
+            vec!["systemd-networkd.service", "systemd-resolved.service"]
+        }
+        NetworkBackend::Networkd => vec!["systemd-networkd.service", "systemd-resolved.service", "iwd.service"],
+        NetworkBackend::Iwd => vec!["iwd.service", "systemd-resolved.service"],
+    }
+}
+
+// True when systemd-resolved answers DNS, /etc/resolv.conf then points at its stub
+fn uses_resolved(network: &NetworkConfig) -> bool {
+    units(network).contains(&"systemd-resolved.service")
+}
+
+// A NetworkManager keyfile for the wired interface or the Wi-Fi network
+fn nm_connection(network: &NetworkConfig, wifi: Option<&WifiConfig>) -> String {
+    let mut file = String::from("[connection]\n");
+    match wifi {
+        Some(wifi) => {
+            file.push_str(&format!("id={}\ntype=wifi\ninterface-name={}\n", wifi.ssid, network.interface));
+            file.push_str(&format!("\n[wifi]\nmode=infrastructure\nssid={}\n", wifi.ssid));
+            if !wifi.passphrase.is_empty() {
+                file.push_str(&format!("\n[wifi-security]\nkey-mgmt=wpa-psk\npsk={}\n", wifi.passphrase));
+            }
+        }
+        None => file.push_str(&format!("id=wired\ntype=ethernet\ninterface-name={}\n", network.interface)),
+    }
+    if network.dhcp {
+        file.push_str("\n[ipv4]\nmethod=auto\n");
+    } else {
+        file.push_str(&format!("\n[ipv4]\nmethod=manual\naddress1={},{}\n", network.address, network.gateway));
+        if !network.dns.is_empty() {
+            file.push_str(&format!("dns={};\n", network.dns.join(";")));
+        }
+    }
+    file.push_str("\n[ipv6]\nmethod=auto\n");
+    file
+}
+
+fn networkd_file(network: &NetworkConfig) -> String {
+    let mut file = format!("[Match]\nName={}\n\n[Network]\n", network.interface);
+    if network.dhcp {
+        file.push_str("DHCP=yes\n");
+    } else {
+        file.push_str(&format!("Address={}\n", network.address));
+        if !network.gateway.is_empty() {
+            file.push_str(&format!("Gateway={}\n", network.gateway));
+        }
+        for dns in &network.dns {
+            file.push_str(&format!("DNS={}\n", dns));
+        }
+    }
+    file
+}
+
+fn iwd_main_conf() -> String {
+    "[General]\nEnableNetworkConfiguration=true\n\n[Network]\nNameResolvingService=systemd\n".to_string()
+}
+
+// The known network file, static addresses only apply when iwd configures the network itself
+fn iwd_network(network: &NetworkConfig, wifi: &WifiConfig) -> String {
+    let mut file = String::new();
+    if !wifi.passphrase.is_empty() {
+        file.push_str(&format!("[Security]\nPassphrase={}\n", wifi.passphrase));
+    }
+    if network.backend == NetworkBackend::Iwd && !network.dhcp {
+        let (address, prefix) = network.address.split_once('/').unwrap_or((&network.address, "24"));
+        if !file.is_empty() {
+            file.push('\n');
+        }
+        file.push_str(&format!("[IPv4]\nAddress={}\n", address));
+        file.push_str(&format!("Netmask={}\n", network::netmask(prefix.parse().unwrap_or(24))));
+        if !network.gateway.is_empty() {
+            file.push_str(&format!("Gateway={}\n", network.gateway));
+        }
+        if !network.dns.is_empty() {
+            file.push_str(&format!("DNS={}\n", network.dns.join(" ")));
+        }
+    }
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_golden;
+
+    fn static_network(backend: NetworkBackend) -> NetworkConfig {
+        NetworkConfig {
+            backend,
+            interface: "enp1s0".to_string(),
+            dhcp: false,
+            address: "192.168.1.10/24".to_string(),
+            gateway: "192.168.1.1".to_string(),
+            dns: vec!["1.1.1.1".to_string(), "9.9.9.9".to_string()],
+            wifi: None,
+        }
+    }
+
+    fn wifi() -> Option<WifiConfig> {
+        Some(WifiConfig {
+            ssid: "Home".to_string(),
+            passphrase: "correct horse".to_string(),
+        })
+    }
+
+    // The contents of the files the answers call for, by path
+    fn wanted(network: &NetworkConfig) -> Vec<(String, String)> {
+        files(network)
+            .into_iter()
+            .filter_map(|file| file.contents.map(|contents| (file.path, contents)))
+            .collect()
+    }
+
+    #[test]
+    fn networkmanager_with_dhcp_needs_no_files() {
+        let network = NetworkConfig::default();
+        assert!(wanted(&network).is_empty());
+        assert_eq!(units(&network), vec!["NetworkManager.service"]);
+    }
+
+    #[test]
+    fn networkmanager_files() {
+        let wired = wanted(&static_network(NetworkBackend::NetworkManager));
+        assert_eq!(wired.len(), 1);
+        assert_golden("networkmanager-wired.nmconnection", &wired[0].1);
+
+        let network = NetworkConfig {
+            interface: "wlan0".to_string(),
+            wifi: wifi(),
+            ..NetworkConfig::default()
+        };
+        let wifi = wanted(&network);
+        assert_eq!(wifi[0].0, NM_WIFI_PATH);
+        assert_golden("networkmanager-wifi.nmconnection", &wifi[0].1);
+    }
+
+    #[test]
+    fn networkd_files() {
+        let network = static_network(NetworkBackend::Networkd);
+        assert_golden("networkd-static.network", &wanted(&network)[0].1);
+        assert!(uses_resolved(&network));
+
+        let network = NetworkConfig {
+            backend: NetworkBackend::Networkd,
+            interface: "wlan0".to_string(),
+            wifi: wifi(),
+            ..NetworkConfig::default()
+        };
+        let files = wanted(&network);
+        assert_eq!(files[1].0, RESOLV_TMPFILES_PATH);
+        assert_eq!(files[2].0, "var/lib/iwd/Home.psk");
+        assert_eq!(files[2].1, "[Security]\nPassphrase=correct horse\n");
+        assert_eq!(packages(&network), vec!["iwd"]);
+    }
+
+    #[test]
+    fn iwd_files() {
+        let network = NetworkConfig {
+            interface: "wlan0".to_string(),
+            wifi: wifi(),
+            ..static_network(NetworkBackend::Iwd)
+        };
+        let files = wanted(&network);
+        assert_eq!(files[0].0, IWD_CONF_PATH);
+        assert_golden("iwd-main.conf", &files[0].1);
+        assert_golden("iwd-static.psk", &files[2].1);
+    }
+}
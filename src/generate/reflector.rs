@@ -0,0 +1,76 @@
+// The [mirrors] answers as reflector options, and the mirrorlist picked offline
+use crate::config::MirrorConfig;
+
+pub const CONF_PATH: &str = "etc/xdg/reflector/reflector.conf";
+pub const MIRRORLIST_PATH: &str = "etc/pacman.d/mirrorlist";
+
+// One option with its value per entry
+pub fn args(mirrors: &MirrorConfig) -> Vec<String> {
+    let mut args = Vec::new();
+    if !mirrors.countries.is_empty() {
+        args.push(format!("--country {}", mirrors.countries.join(",")));
+    }
+    args.push(format!("--protocol {}", mirrors.protocols.join(",")));
+    args.push(format!("--age {}", mirrors.age));
+    args.push(format!("--latest {}", mirrors.count));
+    args.push(format!("--sort {}", mirrors.sort.name()));
+    args.push(format!("--save /{}", MIRRORLIST_PATH));
+    args
+}
+
+// The command to run from the live system before pacstrap
+pub fn command(mirrors: &MirrorConfig) -> String {
+    format!("reflector {}", args(mirrors).join(" "))
+}
+
+// The options for reflector.service on the installed system, one per line
+pub fn conf(mirrors: &MirrorConfig) -> String {
+    let mut conf = "# Options for reflector.service, see reflector --help\n".to_string();
+    for arg in args(mirrors) {
+        conf.push_str(&arg);
+        conf.push('\n');
+    }
+    conf
+}
+
+// A mirrorlist of the servers picked from the local list, None when reflector picks them
+pub fn mirrorlist(mirrors: &MirrorConfig) -> Option<String> {
+    if mirrors.servers.is_empty() {
+        return None;
+    }
+    let mut list = "# Picked offline from the local mirrorlist\n".to_string();
+    for server in &mirrors.servers {
+        list.push_str(&format!("Server = {}\n", server));
+    }
+    Some(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::MirrorSort, tests::assert_golden};
+
+    #[test]
+    fn default_options() {
+        let mirrors = MirrorConfig::default();
+        assert_eq!(
+            command(&mirrors),
+            "reflector --protocol https --age 12 --latest 20 --sort rate --save /etc/pacman.d/mirrorlist"
+        );
+        assert_eq!(mirrorlist(&mirrors), None);
+    }
+
+    #[test]
+    fn countries_and_offline_servers() {
+        let mirrors = MirrorConfig {
+            countries: vec!["CA".to_string(), "US".to_string()],
+            protocols: vec!["https".to_string(), "rsync".to_string()],
+            age: 6,
+            sort: MirrorSort::Score,
+            count: 10,
+            servers: vec!["https://mirrors.kernel.org/archlinux/$repo/os/$arch".to_string()],
+        };
+        assert_golden("reflector.conf", &conf(&mirrors));
+        assert_golden("mirrorlist", &mirrorlist(&mirrors).unwrap());
+    }
+}
@@ -0,0 +1,80 @@
+// sbctl steps for the bootloader script: our own keys enrolled, then every file the firmware starts signed.
+// sbctl keeps the signed files in its database and its pacman hook signs them again on updates.
+use super::bootloader::{self, ESP};
+use crate::config::ArchConfig;
+
+pub fn steps(config: &ArchConfig) -> Vec<String> {
+    let Some(secure_boot) = &config.secure_boot else {
+        return Vec::new();
+    };
+    let mut steps = vec!["pacman -S --needed --noconfirm sbctl".to_string(), "sbctl create-keys".to_string()];
+    // Fails unless the firmware is in setup mode
+    steps.push(match secure_boot.microsoft_keys {
+        true => "sbctl enroll-keys --microsoft".to_string(),
+        false => "sbctl enroll-keys".to_string(),
+    });
+    steps.extend(signed_files(config).iter().map(|file| format!("sbctl sign -s {}", file)));
+    steps
+}
+
+// The bootloader and the kernels, or the UKIs that hold them
+pub fn signed_files(config: &ArchConfig) -> Vec<String> {
+    let mut files = Vec::new();
+    if config.grub.is_some() {
+        files.push(format!("{}/EFI/GRUB/grubx64.efi", ESP));
+    } else if config.systemd_boot.is_some() {
+        files.push(format!("{}/EFI/systemd/systemd-bootx64.efi", ESP));
+        files.push(format!("{}/EFI/BOOT/BOOTX64.EFI", ESP));
+    }
+    for kernel in &config.kernel.kernels {
+        // A leftover `uki = true` under GRUB builds no UKIs to sign
+        files.push(match bootloader::uki(config) {
+            true => format!("/{}", bootloader::uki_path(kernel)),
+            false => format!("{}/vmlinuz-{}", ESP, kernel),
+        });
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{GrubConfig, KernelConfig, SecureBootConfig, SystemdBootConfig},
+        tests::assert_golden,
+    };
+
+    #[test]
+    fn nothing_without_secure_boot() {
+        assert!(steps(&ArchConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn signs_the_ukis() {
+        let config = ArchConfig {
+            systemd_boot: Some(SystemdBootConfig::default()),
+            secure_boot: Some(SecureBootConfig { microsoft_keys: false }),
+            kernel: KernelConfig {
+                kernels: vec!["linux".to_string(), "linux-lts".to_string()],
+                uki: true,
+                ..KernelConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_golden("secure-boot-uki.sh", &(steps(&config).join("\n") + "\n"));
+    }
+
+    #[test]
+    fn grub_signs_kernels_not_ukis() {
+        let config = ArchConfig {
+            grub: Some(GrubConfig::default()),
+            secure_boot: Some(SecureBootConfig::default()),
+            kernel: KernelConfig {
+                uki: true,
+                ..KernelConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_eq!(signed_files(&config), vec!["/boot/EFI/GRUB/grubx64.efi", "/boot/vmlinuz-linux"]);
+    }
+}
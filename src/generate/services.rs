@@ -0,0 +1,78 @@
+// A script installing and enabling what the services need, run inside arch-chroot
+use super::network;
+use crate::{
+    config::{ArchConfig, BootMessages, DisplayManager, SwapKind},
+    services,
+};
+
+pub const SCRIPT_PATH: &str = "root/services.sh";
+
+pub fn script(config: &ArchConfig) -> String {
+    let mut packages = network::packages(&config.network);
+    let mut units = network::units(&config.network);
+    if config.ssh.is_some() {
+        packages.push("openssh");
+        units.push("sshd.service");
+    }
+    if config.swap.kind == SwapKind::Zram {
+        packages.push("zram-generator");
+    }
+    // The initramfs hook shows it
+    if config.kernel.boot_messages == BootMessages::Splash {
+        packages.push("plymouth");
+    }
+    if let Some(desktop) = config.desktop.as_ref().filter(|desktop| desktop.display_manager != DisplayManager::None) {
+        units.push(desktop.display_manager.unit());
+    }
+    for unit in &config.services.enable {
+        packages.extend(services::find(unit).map_or(&[][..], |service| service.packages));
+        units.push(unit);
+    }
+    let mut seen = Vec::new();
+    packages.retain(|package| !seen.contains(package) && { seen.push(*package); true });
+    let mut steps = Vec::new();
+    if !packages.is_empty() {
+        steps.push(format!("pacman -S --needed --noconfirm {}", packages.join(" ")));
+    }
+    steps.push(format!("systemctl enable {}", units.join(" ")));
+    if !config.services.disable.is_empty() {
+        steps.push(format!("systemctl disable {}", config.services.disable.join(" ")));
+    }
+    format!(
+        "#!/bin/bash\n# Install and enable the services picked in the menu, run inside arch-chroot.\nset -euo pipefail\n\n{}\n",
+        steps.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{NetworkBackend, NetworkConfig, ServicesConfig},
+        tests::assert_golden,
+    };
+
+    #[test]
+    fn networkd_services() {
+        let config = ArchConfig {
+            network: NetworkConfig {
+                backend: NetworkBackend::Networkd,
+                ..NetworkConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_golden("services-networkd.sh", &script(&config));
+    }
+
+    #[test]
+    fn picked_services() {
+        let config = ArchConfig {
+            services: ServicesConfig {
+                enable: vec!["fstrim.timer".to_string(), "snapper-timeline.timer".to_string(), "snapper-cleanup.timer".to_string()],
+                disable: vec!["systemd-timesyncd.service".to_string()],
+            },
+            ..ArchConfig::default()
+        };
+        assert_golden("services-picked.sh", &script(&config));
+    }
+}
@@ -0,0 +1,84 @@
+// An sshd_config.d drop-in for the [ssh] answers and the user's authorized keys.
+// The keys go to a root owned file under /etc/ssh so no ownership needs fixing in the chroot.
+use super::GeneratedFile;
+use crate::config::{ArchConfig, SshConfig};
+
+const DROP_IN_PATH: &str = "etc/ssh/sshd_config.d/10-arch-menu.conf";
+pub const AUTHORIZED_KEYS_DIR: &str = "etc/ssh/authorized_keys";
+
+pub fn files(config: &ArchConfig) -> Vec<GeneratedFile> {
+    let ssh = config.ssh.as_ref();
+    let mut files = vec![GeneratedFile {
+        path: DROP_IN_PATH.to_string(),
+        mode: 0o644,
+        contents: ssh.map(drop_in),
+    }];
+    // Keys for other (renamed) users are cleared by write_files, this covers the user's own file
+    if !config.username.is_empty() {
+        files.push(GeneratedFile {
+            path: format!("{}/{}", AUTHORIZED_KEYS_DIR, config.username),
+            mode: 0o644,
+            contents: ssh
+                .filter(|ssh| !ssh.authorized_keys.is_empty())
+                .map(|ssh| ssh.authorized_keys.join("\n") + "\n"),
+        });
+    }
+    files
+}
+
+pub fn drop_in(ssh: &SshConfig) -> String {
+    let yes_no = |flag: bool| if flag { "yes" } else { "no" };
+    let mut conf = "# From the [ssh] answers, read before sshd_config so these values win\n".to_string();
+    conf.push_str(&format!("Port {}\n", ssh.port));
+    conf.push_str(&format!("PasswordAuthentication {}\n", yes_no(ssh.password_authentication)));
+    // PAM would still ask for the password
+    if !ssh.password_authentication {
+        conf.push_str("KbdInteractiveAuthentication no\n");
+    }
+    conf.push_str(&format!("PermitRootLogin {}\n", ssh.permit_root_login.name()));
+    conf.push_str(&format!("AuthorizedKeysFile .ssh/authorized_keys /{}/%u\n", AUTHORIZED_KEYS_DIR));
+    conf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::RootLogin, ssh::tests::ED25519, tests::assert_golden};
+
+    #[test]
+    fn hardened_drop_in_and_keys() {
+        let config = ArchConfig {
+            username: "sam".to_string(),
+            ssh: Some(SshConfig {
+                port: 2222,
+                password_authentication: false,
+                permit_root_login: RootLogin::ProhibitPassword,
+                authorized_keys: vec![ED25519.to_string()],
+            }),
+            ..ArchConfig::default()
+        };
+        let files = files(&config);
+        assert_golden("sshd-drop-in.conf", files[0].contents.as_ref().unwrap());
+        assert_eq!(files[1].path, "etc/ssh/authorized_keys/sam");
+        assert_eq!(files[1].contents.as_deref(), Some(format!("{}\n", ED25519).as_str()));
+    }
+
+    #[test]
+    fn nothing_without_ssh() {
+        let files = files(&ArchConfig::default());
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].contents, None);
+    }
+
+    #[test]
+    fn keys_file_removed_without_keys() {
+        let config = ArchConfig {
+            username: "sam".to_string(),
+            ssh: Some(SshConfig::default()),
+            ..ArchConfig::default()
+        };
+        let files = files(&config);
+        assert_eq!(files[1].path, "etc/ssh/authorized_keys/sam");
+        assert_eq!(files[1].contents, None);
+    }
+}
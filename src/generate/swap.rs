@@ -0,0 +1,115 @@
+// zram-generator's config, or a script making the swapfile, and the resume parameters for hibernation
+use super::{RESUME_OFFSET, ROOT_UUID, SWAP_UUID};
+use crate::config::{ArchConfig, SwapConfig, SwapKind};
+
+pub const ZRAM_CONF_PATH: &str = "etc/systemd/zram-generator.conf";
+pub const SCRIPT_PATH: &str = "root/swap.sh";
+
+// Where the swapfile goes, btrfs keeps it in a subvolume of its own so snapshots leave it out
+pub fn swapfile_path(format_type: &str) -> &'static str {
+    match format_type {
+        "btrfs" => "/swap/swapfile",
+        _ => "/swapfile",
+    }
+}
+
+pub fn zram_conf(swap: &SwapConfig) -> Option<String> {
+    (swap.kind == SwapKind::Zram).then(|| {
+        format!(
+            "[zram0]\nzram-size = ram * {} / 100\ncompression-algorithm = {}\n",
+            swap.zram_percent,
+            swap.zram_algorithm.name()
+        )
+    })
+}
+
+// Run inside arch-chroot, also prints the resume offset when hibernating
+pub fn swapfile_script(config: &ArchConfig) -> Option<String> {
+    let swap = &config.swap;
+    if swap.kind != SwapKind::Swapfile {
+        return None;
+    }
+    let path = swapfile_path(&config.format_type);
+    let mut steps = Vec::new();
+    if config.format_type == "btrfs" {
+        // mkswapfile sets No_COW, which btrfs needs for a swapfile
+        steps.push("btrfs subvolume create /swap".to_string());
+        steps.push(format!("btrfs filesystem mkswapfile --size {} --uuid clear {}", swap.size, path));
+    } else {
+        steps.push(format!("mkswap --file --size {} {}", swap.size, path));
+    }
+    if swap.hibernate {
+        let offset = offset_command(&config.format_type);
+        steps.push(format!("echo \"resume=UUID=$(findmnt -no UUID -T {}) resume_offset=$({})\"", path, offset));
+    }
+    Some(format!(
+        "#!/bin/bash\n# Create the swapfile, run inside arch-chroot.\nset -euo pipefail\n\n{}\n",
+        steps.join("\n")
+    ))
+}
+
+// Prints the swapfile's first physical block, which is what resume_offset wants
+pub fn offset_command(format_type: &str) -> String {
+    let path = swapfile_path(format_type);
+    match format_type {
+        "btrfs" => format!("btrfs inspect-internal map-swapfile -r {}", path),
+        _ => format!("filefrag -v {} | awk '$1 == \"0:\" {{ sub(/\\.\\./, \"\", $4); print $4 }}'", path),
+    }
+}
+
+// Kernel parameters that point hibernation at the swap, with placeholders for what the disk gives
+pub fn resume_params(swap: &SwapConfig) -> Vec<String> {
+    if !swap.hibernate {
+        return Vec::new();
+    }
+    match swap.kind {
+        SwapKind::Partition => vec![format!("resume=UUID={}", SWAP_UUID)],
+        SwapKind::Swapfile => vec![format!("resume=UUID={}", ROOT_UUID), format!("resume_offset={}", RESUME_OFFSET)],
+        SwapKind::Zram | SwapKind::None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ZramAlgorithm, tests::assert_golden};
+
+    fn config(format_type: &str, kind: SwapKind, hibernate: bool) -> ArchConfig {
+        ArchConfig {
+            format_type: format_type.to_string(),
+            swap: SwapConfig {
+                kind,
+                hibernate,
+                ..SwapConfig::default()
+            },
+            ..ArchConfig::default()
+        }
+    }
+
+    #[test]
+    fn zram() {
+        let swap = SwapConfig {
+            zram_percent: 100,
+            zram_algorithm: ZramAlgorithm::Lz4,
+            ..SwapConfig::default()
+        };
+        assert_golden("zram-generator.conf", &zram_conf(&swap).unwrap());
+        assert_eq!(swapfile_script(&config("ext4", SwapKind::Zram, false)), None);
+    }
+
+    #[test]
+    fn swapfiles() {
+        assert_golden("swap-btrfs.sh", &swapfile_script(&config("btrfs", SwapKind::Swapfile, true)).unwrap());
+        assert_golden("swap-ext4.sh", &swapfile_script(&config("ext4", SwapKind::Swapfile, false)).unwrap());
+    }
+
+    #[test]
+    fn resume() {
+        assert!(resume_params(&config("ext4", SwapKind::Swapfile, false).swap).is_empty());
+        assert_eq!(resume_params(&config("ext4", SwapKind::Partition, true).swap), vec!["resume=UUID=@SWAP_UUID@"]);
+        assert_eq!(
+            resume_params(&config("btrfs", SwapKind::Swapfile, true).swap),
+            vec!["resume=UUID=@ROOT_UUID@", "resume_offset=@RESUME_OFFSET@"]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+// The kernels on offer and the command line the answers add up to
+use crate::{
+    config::{ArchConfig, BootMessages, Gpu, Initramfs},
+    generate::{swap, LUKS_UUID, ROOT_UUID},
+};
+
+pub const KERNELS: [(&str, &str); 4] = [
+    ("linux", "The latest stable kernel"),
+    ("linux-lts", "Long term support, a fallback when linux breaks"),
+    ("linux-zen", "Tuned for desktop responsiveness"),
+    ("linux-hardened", "Security patches, some features off"),
+];
+
+pub fn kernel_names() -> Vec<&'static str> {
+    KERNELS.iter().map(|(name, _)| *name).collect()
+}
+
+pub fn description(kernel: &str) -> Option<&'static str> {
+    KERNELS.iter().find(|(name, _)| *name == kernel).map(|(_, description)| *description)
+}
+
+// The mapper name the LUKS container is opened as
+pub const LUKS_NAME: &str = "root";
+
+// Root device, resume, console and GPU parameters, then the ones typed in
+pub fn cmdline(config: &ArchConfig) -> Vec<String> {
+    let mut params = luks_params(config);
+    params.extend(root_params(config));
+    params.extend(swap::resume_params(&config.swap));
+    params.extend(console_params(config));
+    params.extend(extra_params(config));
+    params
+}
+
+// Unlocks the root before it is mounted, sd-encrypt and encrypt read different parameters
+pub fn luks_params(config: &ArchConfig) -> Vec<String> {
+    match (config.storage.encrypt, config.kernel.initramfs) {
+        (false, _) => Vec::new(),
+        (true, Initramfs::Systemd) => vec![format!("rd.luks.name={}={}", LUKS_UUID, LUKS_NAME)],
+        (true, Initramfs::Busybox) => vec![format!("cryptdevice=UUID={}:{}", LUKS_UUID, LUKS_NAME)],
+    }
+}
+
+// What grub-mkconfig works out by itself
+pub fn root_params(config: &ArchConfig) -> Vec<String> {
+    let mut params = match config.storage.encrypt {
+        true => vec![format!("root=/dev/mapper/{}", LUKS_NAME)],
+        false => vec![format!("root=UUID={}", ROOT_UUID)],
+    };
+    params.push("rw".to_string());
+    if config.format_type == "btrfs" {
+        params.push("rootflags=subvol=@".to_string());
+    }
+    params
+}
+
+pub fn console_params(config: &ArchConfig) -> Vec<String> {
+    let console: &[&str] = match config.kernel.boot_messages {
+        BootMessages::Verbose => &[],
+        BootMessages::Quiet => &["quiet", "loglevel=3"],
+        BootMessages::Splash => &["quiet", "loglevel=3", "splash"],
+    };
+    console.iter().map(|param| param.to_string()).collect()
+}
+
+// The GPU's and the typed ones
+pub fn extra_params(config: &ArchConfig) -> Vec<String> {
+    let mut params = Vec::new();
+    if config.kernel.gpu == Gpu::Nvidia {
+        params.push("nvidia_drm.modeset=1".to_string());
+    }
+    params.extend(config.kernel.params.iter().cloned());
+    params
+}
+
+// `key` or `key=value`, nothing the bootloader would need quoted
+pub fn check_param(param: &str) -> Result<(), String> {
+    let (key, value) = param.split_once('=').unwrap_or((param, ""));
+    let key_ok = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+    match key_ok && !value.contains(['"', '\'', '\\']) {
+        true => Ok(()),
+        false => Err(format!("'{}' is not a kernel parameter like key or key=value", param)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{KernelConfig, StorageConfig, SwapConfig, SwapKind};
+
+    #[test]
+    fn plain_ext4() {
+        let config = ArchConfig {
+            format_type: "ext4".to_string(),
+            ..ArchConfig::default()
+        };
+        assert_eq!(cmdline(&config), vec!["root=UUID=@ROOT_UUID@", "rw", "quiet", "loglevel=3"]);
+    }
+
+    #[test]
+    fn everything_adds_up() {
+        let config = ArchConfig {
+            format_type: "btrfs".to_string(),
+            storage: StorageConfig {
+                encrypt: true,
+                ..StorageConfig::default()
+            },
+            swap: SwapConfig {
+                kind: SwapKind::Swapfile,
+                hibernate: true,
+                ..SwapConfig::default()
+            },
+            kernel: KernelConfig {
+                boot_messages: BootMessages::Splash,
+                gpu: Gpu::Nvidia,
+                params: vec!["mitigations=auto".to_string()],
+                ..KernelConfig::default()
+            },
+            ..ArchConfig::default()
+        };
+        assert_eq!(
+            cmdline(&config).join(" "),
+            "rd.luks.name=@LUKS_UUID@=root root=/dev/mapper/root rw rootflags=subvol=@ \
+             resume=UUID=@ROOT_UUID@ resume_offset=@RESUME_OFFSET@ quiet loglevel=3 splash nvidia_drm.modeset=1 mitigations=auto"
+        );
+    }
+
+    #[test]
+    fn busybox_unlocks_with_cryptdevice() {
+        let mut config = ArchConfig::default();
+        config.storage.encrypt = true;
+        config.kernel.initramfs = Initramfs::Busybox;
+        assert_eq!(luks_params(&config), vec!["cryptdevice=UUID=@LUKS_UUID@:root"]);
+    }
+
+    #[test]
+    fn params_are_checked() {
+        assert!(check_param("nowatchdog").is_ok() && check_param("i915.enable_psr=0").is_ok());
+        assert!(check_param("=1").is_err() && check_param("a\"b").is_err() && check_param("x='y'").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+// Logging to a file, the TUI owns the terminal so nothing can go to stderr
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Default location of the log file
+pub const DEFAULT_LOG_FILE: &str = "arch_menu.log";
+// Answers whose name contains one of these are never written to the log
+const SECRET_KEYS: [&str; 3] = ["password", "passphrase", "secret"];
+
+// Log levels, a message is written when its level is <= the configured level
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn label(self) -> &'static str {
+        match self {
+            Level::Off => "OFF",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Level::Off),
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            _ => Err(format!("unknown log level '{}' (off, error, warn, info, debug)", s)),
+        }
+    }
+}
+
+struct Logger {
+    level: Level,
+    file: Mutex<File>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+// Open the log file in append mode, until this is called every message is dropped
+pub fn init(path: &Path, level: Level) -> io::Result<()> {
+    if level == Level::Off {
+        return Ok(());
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)?;
+    let _ = LOGGER.set(Logger {
+        level,
+        file: Mutex::new(file),
+    });
+    Ok(())
+}
+
+// Write one line to the log, use the log_* macros instead of calling this
+pub fn write(level: Level, module: &str, args: fmt::Arguments) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if level > logger.level || level == Level::Off {
+        return;
+    }
+    if let Ok(mut file) = logger.file.lock() {
+        let _ = writeln!(file, "{} {:<5} [{}] {}", timestamp(), level.label(), module, args);
+    }
+}
+
+// Hide the value of secret answers such as the password
+pub fn redact<'a>(name: &str, value: &'a str) -> &'a str {
+    let name = name.to_lowercase();
+    if SECRET_KEYS.iter().any(|key| name.contains(key)) {
+        "********"
+    } else {
+        value
+    }
+}
+
+// Current UTC time as `YYYY-MM-DDTHH:MM:SSZ`
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// Convert days since 1970-01-01 to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Error, module_path!(), format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Warn, module_path!(), format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Info, module_path!(), format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Debug, module_path!(), format_args!($($arg)*)) };
+}
@@ -0,0 +1,62 @@
+// Checks for the network answers and the helpers the generators share
+use std::net::IpAddr;
+
+// `192.168.1.10/24` or `fd00::10/64`
+pub fn check_cidr(value: &str) -> Result<(), String> {
+    let error = || format!("'{}' is not an address with a prefix like 192.168.1.10/24", value);
+    let (address, prefix) = value.split_once('/').ok_or_else(error)?;
+    let address: IpAddr = address.parse().map_err(|_| error())?;
+    let max = if address.is_ipv4() { 32 } else { 128 };
+    match prefix.parse::<u8>() {
+        Ok(prefix) if prefix <= max => Ok(()),
+        _ => Err(error()),
+    }
+}
+
+pub fn check_address(value: &str) -> Result<(), String> {
+    value
+        .parse::<IpAddr>()
+        .map(|_| ())
+        .map_err(|_| format!("'{}' is not an IP address", value))
+}
+
+// The dotted netmask of an IPv4 prefix length, 24 -> 255.255.255.0
+pub fn netmask(prefix: u8) -> String {
+    let mask = u32::MAX.checked_shl(32 - u32::from(prefix.min(32))).unwrap_or(0);
+    let [a, b, c, d] = mask.to_be_bytes();
+    format!("{}.{}.{}.{}", a, b, c, d)
+}
+
+// iwd names its network files after the SSID, hex encoded when it has other characters
+pub fn iwd_file_name(ssid: &str, secured: bool) -> String {
+    let plain = ssid.chars().all(|c| c.is_ascii_alphanumeric() || " _-".contains(c));
+    let name = if plain {
+        ssid.to_string()
+    } else {
+        let hex: String = ssid.bytes().map(|byte| format!("{:02x}", byte)).collect();
+        format!("={}", hex)
+    };
+    format!("{}.{}", name, if secured { "psk" } else { "open" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_addresses() {
+        assert_eq!(check_cidr("192.168.1.10/24"), Ok(()));
+        assert_eq!(check_cidr("fd00::10/64"), Ok(()));
+        assert!(check_cidr("192.168.1.10").is_err());
+        assert!(check_cidr("192.168.1.10/33").is_err());
+        assert!(check_address("1.1.1.1").is_ok() && check_address("one").is_err());
+    }
+
+    #[test]
+    fn netmasks_and_iwd_names() {
+        assert_eq!(netmask(24), "255.255.255.0");
+        assert_eq!(netmask(0), "0.0.0.0");
+        assert_eq!(iwd_file_name("Home Net", true), "Home Net.psk");
+        assert_eq!(iwd_file_name("café", false), "=636166c3a9.open");
+    }
+}
@@ -0,0 +1,82 @@
+// Curated package groups offered on top of the base system
+pub const GROUPS: [(&str, &[&str]); 6] = [
+    ("base-devel", &["base-devel", "git"]),
+    (
+        "networking",
+        &["openssh", "wget", "curl", "rsync", "bind", "nmap", "traceroute", "inetutils"],
+    ),
+    (
+        "fonts",
+        &["noto-fonts", "noto-fonts-cjk", "noto-fonts-emoji", "ttf-dejavu", "ttf-liberation", "ttf-jetbrains-mono"],
+    ),
+    (
+        "multimedia",
+        &["pipewire", "pipewire-alsa", "pipewire-pulse", "wireplumber", "mpv", "ffmpeg", "gst-plugins-good"],
+    ),
+    (
+        "dev-toolchains",
+        &["rustup", "go", "python", "python-pip", "nodejs", "npm", "gcc", "clang", "cmake"],
+    ),
+    ("virtualization", &["qemu-full", "libvirt", "virt-manager", "dnsmasq", "edk2-ovmf", "swtpm"]),
+];
+
+pub fn group_names() -> Vec<&'static str> {
+    GROUPS.iter().map(|(name, _)| *name).collect()
+}
+
+// The packages of the named groups, unknown names are ignored
+pub fn packages_of(groups: &[String]) -> Vec<String> {
+    let packages = GROUPS
+        .iter()
+        .filter(|(name, _)| groups.iter().any(|group| group == name))
+        .flat_map(|(_, packages)| packages.iter().map(|package| package.to_string()));
+    dedupe(packages)
+}
+
+// Split a typed list on spaces and commas
+pub fn parse_list(input: &str) -> Vec<String> {
+    dedupe(
+        input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|name| !name.is_empty())
+            .map(String::from),
+    )
+}
+
+// Package names are lowercase letters, digits and @._+-, not starting with - or .
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(['-', '.'])
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "@._+-".contains(c))
+}
+
+// Drop repeated names, keeping the first one
+pub fn dedupe(packages: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for package in packages {
+        if !unique.contains(&package) {
+            unique.push(package);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_and_typed_packages_are_deduped() {
+        let from_groups = packages_of(&["base-devel".to_string(), "unknown".to_string()]);
+        assert_eq!(from_groups, vec!["base-devel", "git"]);
+        let typed = parse_list("vim, git  htop,vim");
+        assert_eq!(typed, vec!["vim", "git", "htop"]);
+        assert_eq!(dedupe(from_groups.into_iter().chain(typed)), vec!["base-devel", "git", "vim", "htop"]);
+    }
+
+    #[test]
+    fn checks_package_names() {
+        assert!(is_valid_name("visual-studio-code-bin") && is_valid_name("gtk2+"));
+        assert!(!is_valid_name("-rf") && !is_valid_name("Vim") && !is_valid_name("a;b"));
+    }
+}
@@ -0,0 +1,966 @@
+// The questions asked by the menu and where each answer is stored
+use crate::{
+    config::{
+        ArchConfig, AurConfig, AurHelper, BootMessages, DesktopConfig, DisplayManager, Gpu, GrubConfig, GrubTarget,
+        Initramfs, MirrorSort, NetworkBackend, RootLogin, Session, SwapConfig, SwapKind, WifiConfig, ZramAlgorithm,
+    },
+    desktop::{self, Profile},
+    generate::{bootloader, mkinitcpio, reflector, swap},
+    kernel, mirrors, network, packages,
+    size::Size,
+    services, ssh,
+    system::{
+        gpu,
+        interfaces::{self, Interface},
+        keymap, locale,
+        pacman::PackageDb,
+        timezone, SystemInfo,
+    },
+};
+use std::rc::Rc;
+
+// Closures looking at the answers given so far
+type OptionsFn = Box<dyn Fn(&ArchConfig) -> Vec<String>>;
+type VisibleFn = Box<dyn Fn(&ArchConfig) -> bool>;
+type DefaultFn = Box<dyn Fn(&ArchConfig) -> Option<Answer>>;
+type ApplyFn = Box<dyn Fn(&mut ArchConfig, Answer)>;
+type NoteFn = Box<dyn Fn(&ArchConfig, &Answer) -> String>;
+type CheckFn = Box<dyn Fn(&ArchConfig, &Answer) -> Result<(), String>>;
+
+// The LC_* variables that can be set apart from LANG
+const LC_CATEGORIES: [&str; 12] = [
+    "LC_ADDRESS",
+    "LC_COLLATE",
+    "LC_CTYPE",
+    "LC_IDENTIFICATION",
+    "LC_MEASUREMENT",
+    "LC_MESSAGES",
+    "LC_MONETARY",
+    "LC_NAME",
+    "LC_NUMERIC",
+    "LC_PAPER",
+    "LC_TELEPHONE",
+    "LC_TIME",
+];
+
+// Supplementary groups offered for the user
+const USER_GROUPS: [&str; 12] = [
+    "wheel", "audio", "video", "input", "storage", "optical", "network", "power", "lp", "scanner", "docker", "libvirt",
+];
+
+// The option standing for the layout without a variant
+const DEFAULT_VARIANT: &str = "(default)";
+
+// The options of a choice question, either fixed or worked out from earlier answers
+pub enum Choices {
+    Fixed(Vec<String>),
+    FromConfig(OptionsFn),
+}
+
+// Enum to represent different types of questions
+pub enum QuestionType {
+    MultipleChoice { options: Choices },
+    // Any number of options, at least `min` and at most `max` of them
+    MultiSelect { options: Choices, min: usize, max: Option<usize> },
+    FreeText,
+    Boolean,
+    // A whole number, Up/Down change it by `step`
+    Number { min: i64, max: i64, step: i64 },
+    // A size like `512M` or `8G`, percentages only when allowed
+    Size { allow_percent: bool },
+}
+
+// The value given for a question
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+    Text(String),
+    Bool(bool),
+    List(Vec<String>),
+    Number(i64),
+    Size(Size),
+}
+
+impl Answer {
+    // The answer as a string, booleans become "true"/"false"
+    pub fn text(self) -> String {
+        match self {
+            Answer::Text(text) => text,
+            Answer::Bool(flag) => flag.to_string(),
+            Answer::List(items) => items.join(", "),
+            Answer::Number(number) => number.to_string(),
+            Answer::Size(size) => size.to_string(),
+        }
+    }
+
+    pub fn number(&self) -> i64 {
+        match self {
+            Answer::Number(number) => *number,
+            _ => 0,
+        }
+    }
+
+    pub fn size(&self) -> Option<Size> {
+        match self {
+            Answer::Size(size) => Some(*size),
+            _ => None,
+        }
+    }
+
+    pub fn flag(&self) -> bool {
+        matches!(self, Answer::Bool(true))
+    }
+
+    // The answer as a list, a single answer becomes a list of one
+    pub fn list(self) -> Vec<String> {
+        match self {
+            Answer::List(items) => items,
+            other => vec![other.text()],
+        }
+    }
+}
+
+// Structure to represent a question
+pub struct Question {
+    pub prompt: &'static str,
+    pub question_type: QuestionType,
+    // Store the answer in the config
+    pub apply: ApplyFn,
+    // Only ask when this returns true for the answers so far
+    visible: Option<VisibleFn>,
+    // The answer filled in when the question comes up
+    default: Option<DefaultFn>,
+    // Show a line to try out the keyboard
+    pub test_line: bool,
+    // A line below the input worked out from the answer being typed
+    note: Option<NoteFn>,
+    // Refuse answers this returns an error for
+    check: Option<CheckFn>,
+}
+
+impl Question {
+    pub fn new(
+        prompt: &'static str,
+        question_type: QuestionType,
+        apply: impl Fn(&mut ArchConfig, Answer) + 'static,
+    ) -> Self {
+        Question {
+            prompt,
+            question_type,
+            apply: Box::new(apply),
+            visible: None,
+            default: None,
+            test_line: false,
+            note: None,
+            check: None,
+        }
+    }
+
+    // Show a note below the input, updated as the answer changes
+    pub fn with_note(mut self, note: impl Fn(&ArchConfig, &Answer) -> String + 'static) -> Self {
+        self.note = Some(Box::new(note));
+        self
+    }
+
+    pub fn note(&self, config: &ArchConfig, draft: &Answer) -> Option<String> {
+        self.note.as_ref().map(|note| note(config, draft))
+    }
+
+    // Refuse answers `check` returns an error message for, given the answers so far
+    pub fn check(mut self, check: impl Fn(&ArchConfig, &Answer) -> Result<(), String> + 'static) -> Self {
+        self.check = Some(Box::new(check));
+        self
+    }
+
+    pub fn validate(&self, config: &ArchConfig, answer: &Answer) -> Result<(), String> {
+        self.check.as_ref().map_or(Ok(()), |check| check(config, answer))
+    }
+
+    // Add a line where the user can type to check the keyboard layout
+    pub fn with_test_line(mut self) -> Self {
+        self.test_line = true;
+        self
+    }
+
+    // Only ask the question when `visible` holds
+    pub fn when(mut self, visible: impl Fn(&ArchConfig) -> bool + 'static) -> Self {
+        self.visible = Some(Box::new(visible));
+        self
+    }
+
+    // Prefill the answer, options that don't exist are ignored
+    pub fn default_to(mut self, default: impl Fn(&ArchConfig) -> Option<Answer> + 'static) -> Self {
+        self.default = Some(Box::new(default));
+        self
+    }
+
+    pub fn is_visible(&self, config: &ArchConfig) -> bool {
+        self.visible.as_ref().is_none_or(|visible| visible(config))
+    }
+
+    pub fn default_value(&self, config: &ArchConfig) -> Option<Answer> {
+        self.default.as_ref().and_then(|default| default(config))
+    }
+
+    // Every option of a choice question
+    pub fn all_options(&self, config: &ArchConfig) -> Vec<String> {
+        match &self.question_type {
+            QuestionType::MultipleChoice { options } | QuestionType::MultiSelect { options, .. } => match options {
+                Choices::Fixed(options) => options.clone(),
+                Choices::FromConfig(options) => options(config),
+            },
+            QuestionType::Boolean => vec!["Yes".to_string(), "No".to_string()],
+            QuestionType::FreeText | QuestionType::Number { .. } | QuestionType::Size { .. } => Vec::new(),
+        }
+    }
+
+    // The options shown for a choice question, narrowed down by the filter
+    pub fn filtered_options(&self, config: &ArchConfig, filter: &str) -> Vec<String> {
+        if let QuestionType::Boolean = self.question_type {
+            return self.all_options(config);
+        }
+        let filter = filter.to_lowercase();
+        self.all_options(config)
+            .into_iter()
+            .filter(|option| option.to_lowercase().contains(&filter))
+            .collect()
+    }
+}
+
+// Helper to build the option list of a choice question
+fn choice<S: AsRef<str>>(options: &[S]) -> QuestionType {
+    QuestionType::MultipleChoice {
+        options: Choices::Fixed(options.iter().map(|option| option.as_ref().to_string()).collect()),
+    }
+}
+
+// Helper for a choice question whose options depend on earlier answers
+fn choice_from(options: impl Fn(&ArchConfig) -> Vec<String> + 'static) -> QuestionType {
+    QuestionType::MultipleChoice {
+        options: Choices::FromConfig(Box::new(options)),
+    }
+}
+
+// Helper for a question picking several options
+fn multi_select<S: AsRef<str>>(options: &[S], min: usize, max: Option<usize>) -> QuestionType {
+    QuestionType::MultiSelect {
+        options: Choices::Fixed(options.iter().map(|option| option.as_ref().to_string()).collect()),
+        min,
+        max,
+    }
+}
+
+// Helper for a question picking several options that depend on earlier answers
+fn multi_select_from(options: impl Fn(&ArchConfig) -> Vec<String> + 'static) -> QuestionType {
+    QuestionType::MultiSelect {
+        options: Choices::FromConfig(Box::new(options)),
+        min: 0,
+        max: None,
+    }
+}
+
+// The profile picked for the desktop, if any
+fn desktop_profile(config: &ArchConfig) -> Option<&'static Profile> {
+    desktop::profile(&config.desktop.as_ref()?.profile)
+}
+
+// Change the [desktop] table and work out its packages again
+fn update_desktop(config: &mut ArchConfig, change: impl FnOnce(&mut DesktopConfig)) {
+    if let Some(desktop) = &mut config.desktop {
+        change(desktop);
+        desktop.packages = desktop::packages_for(desktop);
+    }
+}
+
+// The names of the enabled locales, for the LANG and LC_* questions
+fn enabled_locale_names(config: &ArchConfig) -> Vec<String> {
+    config
+        .locale
+        .enabled
+        .iter()
+        .map(|line| locale::name_of(line).to_string())
+        .collect()
+}
+
+// Packages typed in by name, checked against the sync databases when there are any
+fn extra_packages(db: Option<Rc<PackageDb>>) -> Question {
+    let question = Question::new("Extra Packages", QuestionType::FreeText, |config, answer| {
+        let typed = packages::parse_list(&answer.text());
+        config.packages = packages::dedupe(packages::packages_of(&config.package_groups).into_iter().chain(typed));
+    });
+    let Some(db) = db else {
+        return question.with_note(|config, draft| {
+            let typed = packages::parse_list(&draft.clone().text());
+            let total = packages::dedupe(config.packages.iter().cloned().chain(typed)).len();
+            format!("Names separated by spaces or commas, {} packages in total", total)
+        });
+    };
+    let check_db = db.clone();
+    question
+        .check(move |_, answer| check_db.check_names(&packages::parse_list(&answer.clone().text())))
+        .with_note(move |config, draft| {
+            let typed = packages::parse_list(&draft.clone().text());
+            let total = packages::dedupe(config.packages.iter().cloned().chain(typed.iter().cloned())).len();
+            let mut note = format!("Names separated by spaces or commas, {} packages in total", total);
+            // One line per typed package with what it is
+            for name in &typed {
+                let description = db.description(name).unwrap_or("not in the sync databases");
+                note.push_str(&format!("\n{}: {}", name, description));
+            }
+            note
+        })
+}
+
+// Run reflector or take the servers from the local mirrorlist, only asked when there is one
+fn mirror_source(list: Option<Rc<str>>) -> Question {
+    let available = list.is_some();
+    let note_list = list.clone();
+    Question::new("Mirror Source", choice(&["reflector", "offline"]), move |config, answer| {
+        config.mirrors.servers = match (&list, answer.text().as_str()) {
+            (Some(list), "offline") => mirrors::filter_mirrorlist(list, &config.mirrors),
+            _ => Vec::new(),
+        }
+    })
+    .when(move |_| available)
+    .with_note(move |config, _| {
+        let count = note_list.as_ref().map_or(0, |list| mirrors::filter_mirrorlist(list, &config.mirrors).len());
+        format!("{} servers in the local mirrorlist match", count)
+    })
+}
+
+// The interface to configure, picked from the detected ones or typed in when there are none
+fn network_interface(interfaces: Vec<Interface>) -> Question {
+    // Options start with the name, `wlan0 (wireless, up, ...)`
+    let apply = |config: &mut ArchConfig, answer: Answer| {
+        config.network.interface = answer.text().split(' ').next().unwrap_or_default().to_string()
+    };
+    if interfaces.is_empty() {
+        return Question::new("Network Interface", QuestionType::FreeText, apply)
+            // iwd only drives wireless cards
+            .default_to(|config| match config.network.backend {
+                NetworkBackend::Iwd => Some(Answer::Text("wlan0".to_string())),
+                _ => Some(Answer::Text(config.network.interface.clone())),
+            })
+            .check(|_, answer| match answer.clone().text().trim() {
+                "" => Err("Enter the interface to configure".to_string()),
+                _ => Ok(()),
+            });
+    }
+    let labels: Vec<String> = interfaces.iter().map(Interface::label).collect();
+    Question::new("Network Interface", choice(&labels), apply).default_to(move |config| {
+        let wireless = config.network.backend == NetworkBackend::Iwd;
+        interfaces::preferred(&interfaces, wireless).map(|interface| Answer::Text(interface.label()))
+    })
+}
+
+// Define the list of questions to be asked
+pub fn default_questions(system: &SystemInfo) -> Vec<Question> {
+    let zones = system.timezones.clone();
+    let current_zone = system.current_timezone.clone();
+    let current_lang = system.current_lang.clone();
+    let wireless: Option<Vec<String>> = (!system.interfaces.is_empty()).then(|| {
+        let wireless = system.interfaces.iter().filter(|interface| interface.wireless);
+        wireless.map(|interface| interface.name.clone()).collect()
+    });
+    let has_ssd = system.has_ssd;
+    let memory = system.memory;
+    let gpus = system.gpus.clone();
+    let uefi = system.uefi;
+    let setup_mode = system.setup_mode;
+    let secure_boot = system.secure_boot;
+    let mut questions = vec![
+        Question::new("Hostname", QuestionType::FreeText, |config, answer| config.hostname = answer.text()),
+        Question::new("Username", QuestionType::FreeText, |config, answer| config.username = answer.text()),
+        Question::new("Password", QuestionType::FreeText, |config, answer| config.password = answer.text()),
+        Question::new("User Groups", multi_select(&USER_GROUPS, 0, None), |config, answer| {
+            config.user_groups = answer.list()
+        })
+        .default_to(|_| Some(Answer::List(vec!["wheel".to_string()]))),
+        // Timezones are picked in two steps, the region stores itself and the city completes it
+        Question::new("Timezone Region", choice(&timezone::regions(&zones)), |config, answer| {
+            config.timezone = answer.text()
+        })
+        .default_to({
+            let current_zone = current_zone.clone();
+            move |_| {
+                let zone = current_zone.as_deref()?;
+                Some(Answer::Text(timezone::region_of(zone).to_string()))
+            }
+        }),
+        Question::new(
+            "Timezone City",
+            choice_from({
+                let zones = zones.clone();
+                move |config| timezone::cities(&zones, &config.timezone)
+            }),
+            |config, answer| {
+                config.timezone = format!("{}/{}", timezone::region_of(&config.timezone), answer.text())
+            },
+        )
+        .when(move |config| !timezone::cities(&zones, &config.timezone).is_empty())
+        .default_to(move |config| {
+            let (region, city) = current_zone.as_deref()?.split_once('/')?;
+            (region == config.timezone).then(|| Answer::Text(city.to_string()))
+        }),
+        // Locales to generate, LANG is picked from these
+        Question::new("Locales", multi_select(&system.locales, 1, None), |config, answer| {
+            config.locale.enabled = answer.list();
+            let names = enabled_locale_names(config);
+            if !names.contains(&config.locale.lang) {
+                config.locale.lang = names.first().cloned().unwrap_or_default();
+            }
+        })
+        .default_to({
+            let current_lang = current_lang.clone();
+            let locales = system.locales.clone();
+            move |_| {
+                let lang = current_lang.as_deref().unwrap_or("en_US.UTF-8");
+                let line = locales.iter().find(|line| locale::name_of(line) == lang)?;
+                Some(Answer::List(vec![line.clone()]))
+            }
+        }),
+        Question::new("Language (LANG)", choice_from(enabled_locale_names), |config, answer| {
+            config.locale.lang = answer.text()
+        })
+        .when(|config| config.locale.enabled.len() > 1)
+        .default_to(move |_| current_lang.clone().map(Answer::Text)),
+        // Pick the LC_* variables to set, each one is asked for below
+        Question::new("Locale Overrides", multi_select(&LC_CATEGORIES, 0, None), |config, answer| {
+            let selected = answer.list();
+            let lang = config.locale.lang.clone();
+            config.locale.overrides.retain(|category, _| selected.contains(category));
+            for category in selected {
+                config.locale.overrides.entry(category).or_insert_with(|| lang.clone());
+            }
+        })
+        .default_to(|config| Some(Answer::List(config.locale.overrides.keys().cloned().collect()))),
+    ];
+    for category in LC_CATEGORIES {
+        questions.push(
+            Question::new(category, choice_from(enabled_locale_names), move |config, answer| {
+                config.locale.overrides.insert(category.to_string(), answer.text());
+            })
+            .when(move |config| config.locale.overrides.contains_key(category))
+            .default_to(move |config| config.locale.overrides.get(category).cloned().map(Answer::Text)),
+        );
+    }
+    questions.extend([
+        // The console keymap and the X11/Wayland layout are set separately
+        Question::new("Console Keymap", choice(&system.keymaps), |config, answer| {
+            config.keyboard.console_keymap = answer.text()
+        })
+        .with_test_line()
+        .default_to({
+            let current_keymap = system.current_keymap.clone();
+            move |_| Some(Answer::Text(current_keymap.clone().unwrap_or_else(|| "us".to_string())))
+        }),
+        Question::new("Keyboard Layout (X11/Wayland)", choice(&system.xkb.layouts), |config, answer| {
+            config.keyboard.xkb_layout = answer.text();
+            config.keyboard.xkb_variant.clear();
+        })
+        .with_test_line()
+        .default_to({
+            let layouts = system.xkb.layouts.clone();
+            move |config| {
+                let layout = keymap::layout_for_keymap(&config.keyboard.console_keymap, &layouts)?;
+                Some(Answer::Text(layout))
+            }
+        }),
+        Question::new(
+            "Keyboard Variant",
+            choice_from({
+                let xkb = system.xkb.clone();
+                move |config| {
+                    let mut variants = vec![DEFAULT_VARIANT.to_string()];
+                    variants.extend(xkb.variants_of(&config.keyboard.xkb_layout));
+                    variants
+                }
+            }),
+            |config, answer| {
+                let variant = answer.text();
+                config.keyboard.xkb_variant = if variant == DEFAULT_VARIANT { String::new() } else { variant };
+            },
+        )
+        .with_test_line()
+        .when({
+            let xkb = system.xkb.clone();
+            move |config| !xkb.variants_of(&config.keyboard.xkb_layout).is_empty()
+        }),
+        Question::new("Format Type", choice(&["btrfs", "ext4", "xfs"]), |config, answer| {
+            config.format_type = answer.text()
+        }),
+        Question::new("Btrfs Compression Level", QuestionType::Number { min: 1, max: 15, step: 1 }, |config, answer| {
+            config.storage.btrfs_compression_level = Some(answer.number() as u8)
+        })
+        .when(|config| config.format_type == "btrfs")
+        .default_to(|config| Some(Answer::Number(config.storage.btrfs_compression_level.unwrap_or(3).into()))),
+        Question::new("Encrypt Root (LUKS)", QuestionType::Boolean, |config, answer| {
+            config.storage.encrypt = answer.flag()
+        })
+        .default_to(|config| Some(Answer::Bool(config.storage.encrypt)))
+        .with_note(|_, _| "The passphrase is asked for by cryptsetup during the install".to_string()),
+        Question::new("EFI Partition Size", QuestionType::Size { allow_percent: false }, |config, answer| {
+            if let Some(size) = answer.size() {
+                config.storage.efi_size = size;
+            }
+        })
+        .default_to(|config| Some(Answer::Size(config.storage.efi_size))),
+        // Swap, hibernation needs it on disk and at least as big as the RAM
+        Question::new("Swap", choice(&SwapKind::ALL.map(SwapKind::name)), |config, answer| {
+            config.swap.kind = SwapKind::from_name(&answer.text()).unwrap_or(SwapKind::Zram);
+            config.swap.hibernate &= config.swap.can_hibernate();
+        })
+        .default_to(|config| Some(Answer::Text(config.swap.kind.name().to_string())))
+        .with_note(|_, draft| {
+            match SwapKind::from_name(&draft.clone().text()) {
+                Some(SwapKind::Zram) => "Compressed swap in RAM, can't hibernate",
+                Some(SwapKind::Swapfile) => "A file on the root filesystem",
+                Some(SwapKind::Partition) => "A partition of its own",
+                _ => "No swap, can't hibernate",
+            }
+            .to_string()
+        }),
+        Question::new("Zram Size (% of RAM)", QuestionType::Number { min: 10, max: 200, step: 10 }, |config, answer| {
+            config.swap.zram_percent = answer.number() as u16
+        })
+        .when(|config| config.swap.kind == SwapKind::Zram)
+        .default_to(|config| Some(Answer::Number(config.swap.zram_percent.into()))),
+        Question::new("Zram Compression", choice(&ZramAlgorithm::ALL.map(ZramAlgorithm::name)), |config, answer| {
+            config.swap.zram_algorithm = ZramAlgorithm::from_name(&answer.text()).unwrap_or(ZramAlgorithm::Zstd)
+        })
+        .when(|config| config.swap.kind == SwapKind::Zram)
+        .default_to(|config| Some(Answer::Text(config.swap.zram_algorithm.name().to_string()))),
+        Question::new("Hibernation", QuestionType::Boolean, |config, answer| config.swap.hibernate = answer.flag())
+            .when(|config| config.swap.can_hibernate())
+            .default_to(|config| Some(Answer::Bool(config.swap.hibernate)))
+            .with_note(|config, draft| {
+                let swap = SwapConfig {
+                    hibernate: draft.clone().flag(),
+                    ..config.swap.clone()
+                };
+                match swap::resume_params(&swap).join(" ") {
+                    params if params.is_empty() => "Suspend to RAM only".to_string(),
+                    params => format!("Kernel parameters: {}", params),
+                }
+            }),
+        Question::new("Swap Size", QuestionType::Size { allow_percent: true }, |config, answer| {
+            if let Some(size) = answer.size() {
+                config.swap.size = size;
+            }
+        })
+        .when(|config| config.swap.can_hibernate())
+        // Room for the whole RAM when hibernating
+        .default_to(move |config| match memory {
+            Some(bytes) if config.swap.hibernate => Some(Answer::Size(Size::gibibytes(bytes.div_ceil(1 << 30)))),
+            _ => Some(Answer::Size(config.swap.size)),
+        })
+        // Only a partition can take a share of the disk, and not one that has to hold the RAM
+        .check(move |config, answer| match (answer.clone().size(), memory) {
+            (Some(Size::Percent(_)), _) if config.swap.kind == SwapKind::Swapfile => {
+                Err("A swapfile needs a size like 8G".to_string())
+            }
+            (Some(Size::Percent(_)), _) if config.swap.hibernate => {
+                Err("Hibernation needs a size like 8G, at least the RAM".to_string())
+            }
+            (Some(Size::Bytes(size)), Some(bytes)) if config.swap.hibernate && size < bytes => {
+                Err(format!("Hibernation needs at least the {} of RAM", Size::Bytes(bytes)))
+            }
+            _ => Ok(()),
+        })
+        .with_note(|config, _| match (config.swap.kind, config.format_type.as_str()) {
+            (SwapKind::Swapfile, "btrfs") => "The swapfile goes in a /swap subvolume of its own".to_string(),
+            (SwapKind::Swapfile, _) => "The swapfile goes in /swapfile".to_string(),
+            _ => "The swap partition is made at this size, or this share of the disk".to_string(),
+        }),
+        // pacman does the install, an AUR helper can be bootstrapped on top
+        Question::new("AUR Helper", choice(&["none", "yay", "paru"]), |config, answer| {
+            config.aur = AurHelper::from_name(&answer.text()).map(|helper| AurConfig {
+                helper,
+                build_user: String::new(),
+                packages: Vec::new(),
+            })
+        }),
+        Question::new("AUR Build User", QuestionType::FreeText, |config, answer| {
+            if let Some(aur) = &mut config.aur {
+                aur.build_user = answer.text()
+            }
+        })
+        .when(|config| config.aur.is_some())
+        .default_to(|config| Some(Answer::Text(config.username.clone())))
+        .check(|_, answer| match answer.clone().text().as_str() {
+            "" => Err("Enter the user that builds AUR packages".to_string()),
+            "root" => Err("makepkg can't build as root, pick another user".to_string()),
+            _ => Ok(()),
+        }),
+        Question::new("AUR Packages", QuestionType::FreeText, |config, answer| {
+            if let Some(aur) = &mut config.aur {
+                aur.packages = packages::parse_list(&answer.text())
+            }
+        })
+        .when(|config| config.aur.is_some())
+        .check(|_, answer| {
+            let typed = packages::parse_list(&answer.clone().text());
+            match typed.into_iter().find(|name| !packages::is_valid_name(name)) {
+                Some(name) => Err(format!("'{}' is not a package name", name)),
+                None => Ok(()),
+            }
+        })
+        .with_note(|config, _| {
+            let helper = config.aur.as_ref().map_or("the helper", |aur| aur.helper.name());
+            format!("Installed with {} after the first boot, names separated by spaces", helper)
+        }),
+        Question::new("Bootloader", choice(&["grub", "systemd-boot"]), move |config, answer| {
+            config.bootloader = answer.text();
+            config.grub = (config.bootloader == "grub").then(|| {
+                let target = if uefi { GrubTarget::Efi } else { GrubTarget::Bios };
+                config.grub.clone().unwrap_or(GrubConfig { target, ..GrubConfig::default() })
+            });
+            config.systemd_boot =
+                (config.bootloader == "systemd-boot").then(|| config.systemd_boot.clone().unwrap_or_default());
+        })
+        .check(move |_, answer| match answer.clone().text().as_str() {
+            "systemd-boot" if !uefi => Err("systemd-boot needs UEFI and this machine booted in BIOS mode".to_string()),
+            _ => Ok(()),
+        }),
+        // Kernels and the command line they boot with
+        Question::new("Kernels", multi_select(&kernel::kernel_names(), 1, None), |config, answer| {
+            config.kernel.kernels = answer.list();
+            if !config.kernel.kernels.contains(&config.kernel.default) {
+                config.kernel.default = config.kernel.kernels[0].clone();
+            }
+        })
+        .default_to(|config| Some(Answer::List(config.kernel.kernels.clone())))
+        .with_note(|_, draft| {
+            let kernels = draft.clone().list();
+            let lines = kernels.iter().filter_map(|name| Some(format!("{}: {}", name, kernel::description(name)?)));
+            lines.collect::<Vec<_>>().join("\n")
+        }),
+        Question::new("Default Kernel", choice_from(|config| config.kernel.kernels.clone()), |config, answer| {
+            config.kernel.default = answer.text()
+        })
+        .when(|config| config.kernel.kernels.len() > 1)
+        .default_to(|config| Some(Answer::Text(config.kernel.default.clone()))),
+        Question::new("Graphics", choice(&Gpu::ALL.map(Gpu::name)), |config, answer| {
+            config.kernel.gpu = Gpu::from_name(&answer.text()).unwrap_or(Gpu::Generic)
+        })
+        .default_to(move |_| Some(Answer::Text(gpu::preferred(&gpus).name().to_string())))
+        .with_note(|_, draft| match Gpu::from_name(&draft.clone().text()) {
+            Some(Gpu::Nvidia) => "Adds nvidia_drm.modeset=1 for Wayland and a console at native resolution".to_string(),
+            _ => "No extra kernel parameters".to_string(),
+        }),
+        Question::new("Boot Messages", choice(&BootMessages::ALL.map(BootMessages::name)), |config, answer| {
+            config.kernel.boot_messages = BootMessages::from_name(&answer.text()).unwrap_or(BootMessages::Quiet)
+        })
+        .default_to(|config| Some(Answer::Text(config.kernel.boot_messages.name().to_string())))
+        .with_note(|_, draft| match BootMessages::from_name(&draft.clone().text()) {
+            Some(BootMessages::Verbose) => "Every kernel and systemd message".to_string(),
+            Some(BootMessages::Splash) => "Quiet, with a splash screen when plymouth is installed".to_string(),
+            _ => "Errors only".to_string(),
+        }),
+        Question::new("Initramfs", choice(&Initramfs::ALL.map(Initramfs::name)), |config, answer| {
+            config.kernel.initramfs = Initramfs::from_name(&answer.text()).unwrap_or(Initramfs::Systemd)
+        })
+        .default_to(|config| Some(Answer::Text(config.kernel.initramfs.name().to_string())))
+        .with_note(|config, draft| {
+            let mut config = config.clone();
+            config.kernel.initramfs = Initramfs::from_name(&draft.clone().text()).unwrap_or(config.kernel.initramfs);
+            format!("HOOKS=({})", mkinitcpio::hooks(&config).join(" "))
+        }),
+        Question::new("Kernel Parameters", QuestionType::FreeText, |config, answer| {
+            config.kernel.params = answer.text().split_whitespace().map(str::to_string).collect()
+        })
+        .default_to(|config| Some(Answer::Text(config.kernel.params.join(" "))))
+        .check(|_, answer| answer.clone().text().split_whitespace().try_for_each(kernel::check_param))
+        // The whole line as it will boot
+        .with_note(|config, draft| {
+            let mut config = config.clone();
+            config.kernel.params = draft.clone().text().split_whitespace().map(str::to_string).collect();
+            kernel::cmdline(&config).join(" ")
+        }),
+        // Bootloader details
+        Question::new("GRUB Target", choice(&GrubTarget::ALL.map(GrubTarget::name)), |config, answer| {
+            if let Some(grub) = &mut config.grub {
+                grub.target = GrubTarget::from_name(&answer.text()).unwrap_or(GrubTarget::Efi)
+            }
+        })
+        .when(|config| config.grub.is_some())
+        .default_to(|config| Some(Answer::Text(config.grub.as_ref()?.target.name().to_string())))
+        .with_note(move |_, _| match uefi {
+            true => format!("This machine booted with UEFI, x86_64-efi installs to the ESP at {}", bootloader::ESP),
+            false => "This machine booted in BIOS mode, i386-pc installs to the disk's boot sector".to_string(),
+        }),
+        Question::new("Boot Menu Timeout (s)", QuestionType::Number { min: 0, max: 30, step: 1 }, |config, answer| {
+            let timeout = answer.number() as u32;
+            if let Some(grub) = &mut config.grub {
+                grub.timeout = timeout
+            }
+            if let Some(systemd_boot) = &mut config.systemd_boot {
+                systemd_boot.timeout = timeout
+            }
+        })
+        .when(|config| config.grub.is_some() || config.systemd_boot.is_some())
+        .default_to(|config| {
+            let timeout = config.grub.as_ref().map(|grub| grub.timeout);
+            let timeout = timeout.or(config.systemd_boot.as_ref().map(|systemd_boot| systemd_boot.timeout))?;
+            Some(Answer::Number(timeout.into()))
+        })
+        .with_note(|_, _| "0 boots the default entry straight away, hold a key to get the menu".to_string()),
+        Question::new("OS Prober", QuestionType::Boolean, |config, answer| {
+            if let Some(grub) = &mut config.grub {
+                grub.os_prober = answer.flag()
+            }
+        })
+        .when(|config| config.grub.is_some())
+        .default_to(|config| Some(Answer::Bool(config.grub.as_ref()?.os_prober)))
+        .with_note(|_, _| "Adds Windows and other installed systems to the menu".to_string()),
+        Question::new("GRUB Theme", QuestionType::FreeText, |config, answer| {
+            if let Some(grub) = &mut config.grub {
+                grub.theme = answer.text()
+            }
+        })
+        .when(|config| config.grub.is_some())
+        .default_to(|config| Some(Answer::Text(config.grub.as_ref()?.theme.clone())))
+        .check(|_, answer| match answer.clone().text() {
+            theme if theme.contains(['/', ' ', '"']) => Err(format!("'{}' is not a theme directory name", theme)),
+            _ => Ok(()),
+        })
+        .with_note(|_, _| "A directory in /usr/share/grub/themes, starfield comes with grub, empty for none".to_string()),
+        Question::new("Boot Menu Editor", QuestionType::Boolean, |config, answer| {
+            if let Some(systemd_boot) = &mut config.systemd_boot {
+                systemd_boot.editor = answer.flag()
+            }
+        })
+        .when(|config| config.systemd_boot.is_some())
+        .default_to(|config| Some(Answer::Bool(config.systemd_boot.as_ref()?.editor)))
+        .with_note(|_, _| "Editing the command line at boot lets anyone at the keyboard start a root shell".to_string()),
+        Question::new("Unified Kernel Image", QuestionType::Boolean, |config, answer| config.kernel.uki = answer.flag())
+            .when(|config| config.systemd_boot.is_some())
+            .default_to(|config| Some(Answer::Bool(config.kernel.uki)))
+            .with_note(|_, _| {
+                "Kernel, initramfs and command line in one file in /boot/EFI/Linux, found by systemd-boot without entries"
+                    .to_string()
+            }),
+        // Secure Boot with our own keys, only for an EFI install
+        Question::new("Secure Boot", QuestionType::Boolean, |config, answer| {
+            config.secure_boot = answer.flag().then(|| config.secure_boot.clone().unwrap_or_default())
+        })
+        .when(move |config| uefi && bootloader::uses_esp(config))
+        .default_to(move |config| Some(Answer::Bool(config.secure_boot.is_some() || secure_boot == Some(true))))
+        .with_note(move |_, _| match setup_mode {
+            Some(true) => "The firmware is in setup mode, sbctl can enroll its keys".to_string(),
+            Some(false) => {
+                "The firmware is not in setup mode: clear the Secure Boot keys in its setup first or enrolling the keys will fail"
+                    .to_string()
+            }
+            None => "The Secure Boot state couldn't be read, the firmware needs to be in setup mode".to_string(),
+        }),
+        Question::new("Microsoft Keys", QuestionType::Boolean, |config, answer| {
+            if let Some(secure_boot) = &mut config.secure_boot {
+                secure_boot.microsoft_keys = answer.flag()
+            }
+        })
+        .when(|config| config.secure_boot.is_some())
+        .default_to(|config| Some(Answer::Bool(config.secure_boot.as_ref()?.microsoft_keys)))
+        .with_note(|_, _| {
+            "Firmware drivers on graphics cards and docks are signed by Microsoft, without its keys they may not load"
+                .to_string()
+        }),
+        // Extra packages, curated groups first and then anything else by name
+        Question::new("Package Groups", multi_select(&packages::group_names(), 0, None), |config, answer| {
+            config.package_groups = answer.list();
+            config.packages = packages::packages_of(&config.package_groups);
+        })
+        .default_to(|config| Some(Answer::List(config.package_groups.clone())))
+        .with_note(|_, draft| {
+            let count = packages::packages_of(&draft.clone().list()).len();
+            format!("{} packages selected", count)
+        }),
+        extra_packages(system.packages.clone()),
+        // A desktop profile, then its display manager, session and extras
+        Question::new(
+            "Desktop Profile",
+            choice(&[&["none"], desktop::profile_names().as_slice()].concat()),
+            |config, answer| config.desktop = desktop::profile(&answer.text()).map(|profile| profile.defaults()),
+        ),
+        Question::new("Display Manager", choice(&DisplayManager::ALL.map(DisplayManager::name)), |config, answer| {
+            let manager = DisplayManager::from_name(&answer.text()).unwrap_or(DisplayManager::None);
+            update_desktop(config, |desktop| desktop.display_manager = manager)
+        })
+        .when(|config| config.desktop.is_some())
+        .default_to(|config| Some(Answer::Text(config.desktop.as_ref()?.display_manager.name().to_string()))),
+        Question::new(
+            "Session Type",
+            choice_from(|config| {
+                let sessions = desktop_profile(config).map_or(&[][..], |profile| profile.sessions);
+                sessions.iter().map(|session| session.name().to_string()).collect()
+            }),
+            |config, answer| {
+                if let Some(session) = Session::from_name(&answer.text()) {
+                    update_desktop(config, |desktop| desktop.session = session)
+                }
+            },
+        )
+        // Only asked when the profile runs on both
+        .when(|config| desktop_profile(config).is_some_and(|profile| profile.sessions.len() > 1))
+        .default_to(|config| Some(Answer::Text(config.desktop.as_ref()?.session.name().to_string()))),
+        Question::new(
+            "Desktop Extras",
+            multi_select_from(|config| desktop_profile(config).map(|profile| profile.extra_names()).unwrap_or_default()),
+            |config, answer| update_desktop(config, |desktop| desktop.extras = answer.list()),
+        )
+        .when(|config| desktop_profile(config).is_some_and(|profile| !profile.extras.is_empty())),
+        // Mirrors, the options for reflector
+        Question::new("Mirror Countries", multi_select(&mirrors::country_options(), 0, None), |config, answer| {
+            config.mirrors.countries = answer.list().iter().map(|option| mirrors::code_of(option).to_string()).collect()
+        })
+        .default_to(|config| {
+            let options = config.mirrors.countries.iter().filter_map(|code| mirrors::option_for(code)).collect();
+            Some(Answer::List(options))
+        })
+        .with_note(|_, draft| match draft.clone().list().len() {
+            0 => "None picked uses mirrors worldwide".to_string(),
+            count => format!("{} picked", count),
+        }),
+        Question::new("Mirror Protocols", multi_select(&["https", "http", "rsync"], 1, None), |config, answer| {
+            config.mirrors.protocols = answer.list()
+        })
+        .default_to(|config| Some(Answer::List(config.mirrors.protocols.clone()))),
+        Question::new("Mirror Age (hours)", QuestionType::Number { min: 1, max: 168, step: 6 }, |config, answer| {
+            config.mirrors.age = answer.number() as u32
+        })
+        .default_to(|config| Some(Answer::Number(config.mirrors.age.into()))),
+        Question::new("Mirror Count", QuestionType::Number { min: 1, max: 200, step: 5 }, |config, answer| {
+            config.mirrors.count = answer.number() as u32
+        })
+        .default_to(|config| Some(Answer::Number(config.mirrors.count.into()))),
+        Question::new("Mirror Sort", choice(&MirrorSort::ALL.map(MirrorSort::name)), |config, answer| {
+            config.mirrors.sort = MirrorSort::from_name(&answer.text()).unwrap_or(MirrorSort::Rate)
+        })
+        .default_to(|config| Some(Answer::Text(config.mirrors.sort.name().to_string())))
+        .with_note(|config, draft| {
+            let mut mirrors = config.mirrors.clone();
+            mirrors.sort = MirrorSort::from_name(&draft.clone().text()).unwrap_or(mirrors.sort);
+            reflector::command(&mirrors)
+        }),
+        mirror_source(system.mirrorlist.clone()),
+        // Networking on the installed system
+        Question::new("Network Backend", choice(&NetworkBackend::ALL.map(NetworkBackend::name)), |config, answer| {
+            config.network.backend = NetworkBackend::from_name(&answer.text()).unwrap_or(NetworkBackend::NetworkManager)
+        })
+        .default_to(|config| Some(Answer::Text(config.network.backend.name().to_string()))),
+        network_interface(system.interfaces.clone()),
+        Question::new("IP Configuration", choice(&["DHCP", "static"]), |config, answer| {
+            config.network.dhcp = answer.text() == "DHCP";
+            if config.network.dhcp {
+                config.network.address.clear();
+                config.network.gateway.clear();
+                config.network.dns.clear();
+            }
+        }),
+        Question::new("IP Address", QuestionType::FreeText, |config, answer| config.network.address = answer.text())
+            .when(|config| !config.network.dhcp)
+            .check(|_, answer| network::check_cidr(&answer.clone().text()))
+            .with_note(|_, _| "With the prefix length, e.g. 192.168.1.10/24".to_string()),
+        Question::new("Gateway", QuestionType::FreeText, |config, answer| config.network.gateway = answer.text())
+            .when(|config| !config.network.dhcp)
+            .check(|_, answer| network::check_address(&answer.clone().text())),
+        Question::new("DNS Servers", QuestionType::FreeText, |config, answer| {
+            config.network.dns = packages::parse_list(&answer.text())
+        })
+        .when(|config| !config.network.dhcp)
+        .check(|_, answer| packages::parse_list(&answer.clone().text()).iter().try_for_each(|dns| network::check_address(dns)))
+        .with_note(|_, _| "Separated by spaces or commas".to_string()),
+        Question::new("Wi-Fi Network (SSID)", QuestionType::FreeText, |config, answer| {
+            let ssid = answer.text();
+            config.network.wifi = (!ssid.is_empty()).then(|| WifiConfig {
+                ssid,
+                passphrase: String::new(),
+            })
+        })
+        // Only for a wireless card, or when the interfaces are unknown
+        .when(move |config| wireless.as_ref().is_none_or(|wireless| wireless.contains(&config.network.interface)))
+        .check(|_, answer| match answer.clone().text().len() {
+            0..=32 => Ok(()),
+            _ => Err("An SSID is at most 32 bytes".to_string()),
+        })
+        .with_note(|_, _| "Leave empty for a wired connection".to_string()),
+        Question::new("Wi-Fi Passphrase", QuestionType::FreeText, |config, answer| {
+            if let Some(wifi) = &mut config.network.wifi {
+                wifi.passphrase = answer.text()
+            }
+        })
+        .when(|config| config.network.wifi.is_some())
+        .check(|_, answer| match answer.clone().text().len() {
+            0 | 8..=63 => Ok(()),
+            _ => Err("A WPA passphrase has 8 to 63 characters".to_string()),
+        })
+        .with_note(|_, _| "Leave empty for an open network".to_string()),
+        // The SSH server, keys come before the password question so it can refuse a lock out
+        Question::new("Enable SSH", QuestionType::Boolean, |config, answer| {
+            config.enable_ssh = answer.flag();
+            config.ssh = config.enable_ssh.then(|| config.ssh.clone().unwrap_or_default());
+        }),
+        Question::new("SSH Port", QuestionType::Number { min: 1, max: 65535, step: 1 }, |config, answer| {
+            if let Some(ssh) = &mut config.ssh {
+                ssh.port = answer.number() as u16
+            }
+        })
+        .when(|config| config.ssh.is_some())
+        .default_to(|config| Some(Answer::Number(config.ssh.as_ref()?.port.into()))),
+        Question::new("SSH Authorized Keys", QuestionType::FreeText, |config, answer| {
+            if let Some(ssh) = &mut config.ssh {
+                ssh.authorized_keys = ssh::load_keys(&answer.text()).unwrap_or_default()
+            }
+        })
+        .when(|config| config.ssh.is_some())
+        .check(|_, answer| ssh::load_keys(&answer.clone().text()).map(|_| ()))
+        .with_note(|config, _| {
+            format!("Paste a public key or give a file of keys (~/.ssh/id_ed25519.pub) for {}", config.username)
+        }),
+        Question::new("SSH Password Login", QuestionType::Boolean, |config, answer| {
+            if let Some(ssh) = &mut config.ssh {
+                ssh.password_authentication = answer.flag()
+            }
+        })
+        .when(|config| config.ssh.is_some())
+        // Only keys by default once there are some
+        .default_to(|config| Some(Answer::Bool(config.ssh.as_ref()?.authorized_keys.is_empty())))
+        .check(|config, answer| match &config.ssh {
+            Some(ssh) if !answer.flag() && ssh.authorized_keys.is_empty() => {
+                Err("Without a password login an authorized key is needed to get in".to_string())
+            }
+            _ => Ok(()),
+        }),
+        Question::new("SSH Root Login", choice(&RootLogin::ALL.map(RootLogin::name)), |config, answer| {
+            if let Some(ssh) = &mut config.ssh {
+                ssh.permit_root_login = RootLogin::from_name(&answer.text()).unwrap_or(RootLogin::No)
+            }
+        })
+        .when(|config| config.ssh.is_some()),
+        // Services, the suggestions depend on the answers above
+        Question::new("Services", multi_select(&services::options(), 0, None), |config, answer| {
+            config.services.enable = answer.list().iter().map(|option| services::unit_of(option).to_string()).collect()
+        })
+        .default_to(move |config| {
+            let units = match config.services.enable.is_empty() {
+                true => services::suggested(config, has_ssd).iter().map(|unit| unit.to_string()).collect(),
+                false => config.services.enable.clone(),
+            };
+            Some(Answer::List(units.iter().filter_map(|unit| services::find(unit)).map(services::label).collect()))
+        })
+        .with_note(move |config, _| {
+            format!("Suggested here: {}", services::suggested(config, has_ssd).join(", "))
+        }),
+        Question::new("Services to Disable", QuestionType::FreeText, |config, answer| {
+            config.services.disable = packages::parse_list(&answer.text())
+        })
+        .check(|_, answer| {
+            let units = packages::parse_list(&answer.clone().text());
+            match units.iter().find(|unit| !services::is_unit_name(unit)) {
+                Some(unit) => Err(format!("{} is not a unit name like cups.service", unit)),
+                None => Ok(()),
+            }
+        })
+        .with_note(|_, _| "Units to keep off, separated by spaces or commas".to_string()),
+    ]);
+    questions
+}
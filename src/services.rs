@@ -0,0 +1,145 @@
+// The systemd units offered in the services step and which ones the answers suggest
+use crate::config::ArchConfig;
+
+pub struct Service {
+    pub unit: &'static str,
+    // Packages the unit comes with, none when it is part of the base system
+    pub packages: &'static [&'static str],
+    pub description: &'static str,
+}
+
+pub const CATALOG: [Service; 13] = [
+    Service {
+        unit: "systemd-timesyncd.service",
+        packages: &[],
+        description: "Keep the clock in sync over the network",
+    },
+    Service {
+        unit: "fstrim.timer",
+        packages: &[],
+        description: "Weekly TRIM of SSDs",
+    },
+    Service {
+        unit: "reflector.timer",
+        packages: &["reflector"],
+        description: "Refresh the mirrorlist weekly",
+    },
+    Service {
+        unit: "paccache.timer",
+        packages: &["pacman-contrib"],
+        description: "Trim the package cache weekly",
+    },
+    Service {
+        unit: "snapper-timeline.timer",
+        packages: &["snapper"],
+        description: "Hourly btrfs snapshots",
+    },
+    Service {
+        unit: "snapper-cleanup.timer",
+        packages: &["snapper"],
+        description: "Prune old btrfs snapshots",
+    },
+    Service {
+        unit: "bluetooth.service",
+        packages: &["bluez", "bluez-utils"],
+        description: "Bluetooth",
+    },
+    Service {
+        unit: "cups.service",
+        packages: &["cups"],
+        description: "Printing",
+    },
+    Service {
+        unit: "avahi-daemon.service",
+        packages: &["avahi", "nss-mdns"],
+        description: "Find printers and hosts on the local network",
+    },
+    Service {
+        unit: "firewalld.service",
+        packages: &["firewalld"],
+        description: "Firewall",
+    },
+    Service {
+        unit: "power-profiles-daemon.service",
+        packages: &["power-profiles-daemon"],
+        description: "Power profiles for laptops",
+    },
+    Service {
+        unit: "docker.service",
+        packages: &["docker"],
+        description: "Docker containers",
+    },
+    Service {
+        unit: "libvirtd.service",
+        packages: &["libvirt"],
+        description: "Virtual machines with libvirt",
+    },
+];
+
+// Options for the services question, `fstrim.timer - Weekly TRIM of SSDs`
+pub fn options() -> Vec<String> {
+    CATALOG.iter().map(label).collect()
+}
+
+pub fn label(service: &Service) -> String {
+    format!("{} - {}", service.unit, service.description)
+}
+
+// The unit at the start of an option
+pub fn unit_of(option: &str) -> &str {
+    option.split(' ').next().unwrap_or(option)
+}
+
+pub fn find(unit: &str) -> Option<&'static Service> {
+    CATALOG.iter().find(|service| service.unit == unit)
+}
+
+// Units worth enabling given the other answers and the machine
+pub fn suggested(config: &ArchConfig, has_ssd: bool) -> Vec<&'static str> {
+    let btrfs = config.format_type == "btrfs";
+    let wanted = |unit: &str| match unit {
+        "systemd-timesyncd.service" | "reflector.timer" | "paccache.timer" => true,
+        "fstrim.timer" => has_ssd,
+        "snapper-timeline.timer" | "snapper-cleanup.timer" => btrfs,
+        "bluetooth.service" => config.desktop.is_some(),
+        "docker.service" => config.user_groups.iter().any(|group| group == "docker"),
+        "libvirtd.service" => {
+            config.package_groups.iter().any(|group| group == "virtualization")
+                || config.user_groups.iter().any(|group| group == "libvirt")
+        }
+        _ => false,
+    };
+    CATALOG.iter().map(|service| service.unit).filter(|unit| wanted(unit)).collect()
+}
+
+// A systemd unit name, `name.service`, `name.timer`, ...
+pub fn is_unit_name(name: &str) -> bool {
+    const SUFFIXES: [&str; 6] = [".service", ".timer", ".socket", ".mount", ".path", ".target"];
+    SUFFIXES.iter().any(|suffix| {
+        name.strip_suffix(suffix)
+            .is_some_and(|stem| !stem.is_empty() && stem.chars().all(|c| c.is_ascii_alphanumeric() || "-_.@:\\".contains(c)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_follow_the_answers() {
+        let mut config = ArchConfig::default();
+        assert_eq!(suggested(&config, false), vec!["systemd-timesyncd.service", "reflector.timer", "paccache.timer"]);
+        config.format_type = "btrfs".to_string();
+        config.user_groups = vec!["docker".to_string()];
+        let suggested = suggested(&config, true);
+        assert!(suggested.contains(&"fstrim.timer") && suggested.contains(&"snapper-cleanup.timer"));
+        assert!(suggested.contains(&"docker.service") && !suggested.contains(&"bluetooth.service"));
+    }
+
+    #[test]
+    fn unit_names() {
+        assert_eq!(unit_of(&label(&CATALOG[1])), "fstrim.timer");
+        assert!(is_unit_name("getty@tty1.service") && is_unit_name("fstrim.timer"));
+        assert!(!is_unit_name("fstrim") && !is_unit_name(".service") && !is_unit_name("a b.service"));
+    }
+}
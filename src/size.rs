@@ -0,0 +1,111 @@
+// Sizes typed as `512M`, `8G` or `50%`
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+const UNITS: [(char, u64); 4] = [('T', 1 << 40), ('G', 1 << 30), ('M', 1 << 20), ('K', 1 << 10)];
+
+// A size in bytes, or a share of what is left on the disk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    Bytes(u64),
+    Percent(u8),
+}
+
+impl Size {
+    pub const fn gibibytes(gib: u64) -> Self {
+        Size::Bytes(gib << 30)
+    }
+}
+
+impl fmt::Display for Size {
+    // The largest unit that divides the size exactly, `1536M` rather than `1.5G`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Size::Percent(percent) => write!(f, "{}%", percent),
+            Size::Bytes(bytes) => {
+                for (unit, factor) in UNITS {
+                    if *bytes >= factor && bytes % factor == 0 {
+                        return write!(f, "{}{}", bytes / factor, unit);
+                    }
+                }
+                write!(f, "{}", bytes)
+            }
+        }
+    }
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    // Binary units, `K`, `M`, `G`, `T` with an optional `iB`/`B`, case doesn't matter
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            return match percent.trim().parse::<u8>() {
+                Ok(percent @ 1..=100) => Ok(Size::Percent(percent)),
+                _ => Err(format!("'{}' is not a percentage between 1% and 100%", s)),
+            };
+        }
+        let upper = s.to_uppercase();
+        let number = upper.trim_end_matches("IB").trim_end_matches('B');
+        let (digits, factor) = match number.chars().last() {
+            Some(unit) if unit.is_ascii_alphabetic() => {
+                let factor = UNITS
+                    .iter()
+                    .find(|(name, _)| *name == unit)
+                    .map(|(_, factor)| *factor)
+                    .ok_or_else(|| format!("unknown unit '{}' in '{}', use K, M, G or T", unit, s))?;
+                (&number[..number.len() - 1], factor)
+            }
+            _ => (number, 1),
+        };
+        let value: u64 = digits
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' is not a size like 512M, 8G or 50%", s))?;
+        match value.checked_mul(factor) {
+            Some(0) => Err("the size can't be zero".to_string()),
+            Some(bytes) => Ok(Size::Bytes(bytes)),
+            None => Err(format!("'{}' is too large", s)),
+        }
+    }
+}
+
+// Written as the same `8G` string the user typed
+impl Serialize for Size {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Size {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_and_percentages() {
+        assert_eq!("512M".parse(), Ok(Size::Bytes(512 << 20)));
+        assert_eq!("8g".parse(), Ok(Size::gibibytes(8)));
+        assert_eq!("1GiB".parse(), Ok(Size::gibibytes(1)));
+        assert_eq!("2048".parse(), Ok(Size::Bytes(2048)));
+        assert_eq!("50%".parse(), Ok(Size::Percent(50)));
+        assert!("0M".parse::<Size>().is_err());
+        assert!("150%".parse::<Size>().is_err());
+        assert!("8X".parse::<Size>().is_err());
+        assert!("big".parse::<Size>().is_err());
+    }
+
+    #[test]
+    fn displays_the_largest_exact_unit() {
+        assert_eq!(Size::Bytes(1536 << 20).to_string(), "1536M");
+        assert_eq!(Size::Bytes(2048 << 20).to_string(), "2G");
+        assert_eq!(Size::Percent(100).to_string(), "100%");
+    }
+}
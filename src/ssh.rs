@@ -0,0 +1,122 @@
+// Authorized keys for the [ssh] table: checking their format and loading them from a file
+use std::{env, fs, path::PathBuf};
+
+// Key types sshd accepts
+const KEY_TYPES: [&str; 7] = [
+    "ssh-ed25519",
+    "ssh-rsa",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ssh-ed25519@openssh.com",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+];
+
+// `<type> <base64 blob> [comment]`, where the blob starts with the type again
+pub fn check_key(line: &str) -> Result<(), String> {
+    let mut fields = line.split_whitespace();
+    let (Some(kind), Some(blob)) = (fields.next(), fields.next()) else {
+        return Err("A public key looks like 'ssh-ed25519 AAAA... comment'".to_string());
+    };
+    if !KEY_TYPES.contains(&kind) {
+        return Err(format!("Unknown key type '{}'", kind));
+    }
+    let blob = decode_base64(blob).ok_or_else(|| format!("The {} key isn't valid base64", kind))?;
+    // The blob is the type as a length prefixed string followed by the key itself
+    let name_len = blob.get(..4).map(|len| u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize);
+    match name_len.and_then(|len| blob.get(4..4 + len)) {
+        Some(name) if name == kind.as_bytes() && blob.len() > 4 + kind.len() => Ok(()),
+        _ => Err(format!("The key data doesn't match its type {}", kind)),
+    }
+}
+
+// The keys in an answer: a pasted key, or the path of a file of keys (`~/.ssh/id_ed25519.pub`).
+// Blank lines and comments in files are skipped.
+pub fn load_keys(input: &str) -> Result<Vec<String>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !input.starts_with(['/', '~', '.']) {
+        check_key(input)?;
+        return Ok(vec![input.to_string()]);
+    }
+    let path = match input.strip_prefix("~/") {
+        Some(rest) => env::var_os("HOME").map(PathBuf::from).unwrap_or_default().join(rest),
+        None => PathBuf::from(input),
+    };
+    let contents = fs::read_to_string(&path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+    let keys: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    for (number, key) in keys.iter().enumerate() {
+        check_key(key).map_err(|e| format!("Key {} of {}: {}", number + 1, path.display(), e))?;
+    }
+    if keys.is_empty() {
+        return Err(format!("No keys in {}", path.display()));
+    }
+    Ok(keys)
+}
+
+// Standard base64 with padding, None for anything else
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    for chunk in text.as_bytes().chunks(4) {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 {
+            return None;
+        }
+        let mut group = 0u32;
+        for &c in &chunk[..4 - padding] {
+            group = group << 6 | u32::from(value(c)?);
+        }
+        group <<= 6 * padding as u32;
+        bytes.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    pub const ED25519: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f sam@laptop";
+
+    #[test]
+    fn checks_key_format() {
+        assert_eq!(check_key(ED25519), Ok(()));
+        assert!(check_key("ssh-ed25519").is_err());
+        assert!(check_key("ssh-dss AAAAB3NzaC1kc3MAAACBAP").is_err());
+        assert!(check_key("ssh-ed25519 not*base64").is_err());
+        // An RSA blob passed off as ed25519
+        assert!(check_key("ssh-ed25519 AAAAB3NzaC1yc2EAAAADAQABAAABAQ==").is_err());
+    }
+
+    #[test]
+    fn loads_keys_from_a_file() {
+        let dir = temp_dir("keys");
+        let path = dir.join("keys.pub");
+        fs::write(&path, format!("# laptop\n{}\n\n{}\n", ED25519, ED25519)).unwrap();
+        assert_eq!(load_keys(path.to_str().unwrap()).unwrap().len(), 2);
+        assert_eq!(load_keys(ED25519).unwrap(), vec![ED25519]);
+        assert_eq!(load_keys("  "), Ok(Vec::new()));
+        fs::write(&path, "ssh-ed25519 AAAA\n").unwrap();
+        assert!(load_keys(path.to_str().unwrap()).unwrap_err().starts_with("Key 1 of"));
+    }
+}
@@ -0,0 +1,45 @@
+// Block devices from /sys/block
+use std::{fs, path::Path};
+
+use super::under_root;
+
+const SYS_BLOCK: &str = "/sys/block";
+// Not disks an install goes to
+const VIRTUAL_PREFIXES: [&str; 5] = ["loop", "ram", "zram", "sr", "dm-"];
+
+// True when a fixed disk doesn't spin, `queue/rotational` is 0
+pub fn has_ssd(root: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(under_root(root, SYS_BLOCK)) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let read = |file: &str| fs::read_to_string(entry.path().join(file)).map(|value| value.trim().to_string());
+        !VIRTUAL_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+            && read("removable").is_ok_and(|removable| removable == "0")
+            && read("queue/rotational").is_ok_and(|rotational| rotational == "0")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    fn add(root: &Path, name: &str, rotational: &str) {
+        let dir = under_root(root, SYS_BLOCK).join(name);
+        fs::create_dir_all(dir.join("queue")).unwrap();
+        fs::write(dir.join("removable"), "0\n").unwrap();
+        fs::write(dir.join("queue/rotational"), format!("{}\n", rotational)).unwrap();
+    }
+
+    #[test]
+    fn finds_ssds() {
+        let root = temp_dir("disks");
+        add(&root, "sda", "1");
+        add(&root, "loop0", "0");
+        assert!(!has_ssd(&root));
+        add(&root, "nvme0n1", "0");
+        assert!(has_ssd(&root));
+    }
+}
@@ -0,0 +1,63 @@
+// How the machine booted, from /sys/firmware
+use std::{fs, path::Path};
+
+use super::under_root;
+
+const FIRMWARE: &str = "/sys/firmware";
+const EFI: &str = "/sys/firmware/efi";
+const EFIVARS: &str = "/sys/firmware/efi/efivars";
+// The vendor GUID of the UEFI global variables
+const GLOBAL_VARIABLE: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+
+// UEFI when the kernel exposes efi, assumed when /sys isn't there at all
+pub fn is_uefi(root: &Path) -> bool {
+    under_root(root, EFI).is_dir() || !under_root(root, FIRMWARE).is_dir()
+}
+
+// A one byte global variable, efivarfs puts four bytes of attributes first
+fn global_flag(root: &Path, name: &str) -> Option<bool> {
+    let path = under_root(root, EFIVARS).join(format!("{}-{}", name, GLOBAL_VARIABLE));
+    let bytes = fs::read(path).ok()?;
+    bytes.get(4).map(|value| *value == 1)
+}
+
+// Setup mode takes new keys without them being signed, so sbctl can enroll its own
+pub fn setup_mode(root: &Path) -> Option<bool> {
+    global_flag(root, "SetupMode")
+}
+
+pub fn secure_boot(root: &Path) -> Option<bool> {
+    global_flag(root, "SecureBoot")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    pub fn write_efivar(root: &Path, name: &str, value: u8) {
+        let dir = under_root(root, EFIVARS);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{}-{}", name, GLOBAL_VARIABLE)), [6, 0, 0, 0, value]).unwrap();
+    }
+
+    #[test]
+    fn bios_without_efi() {
+        let root = temp_dir("firmware");
+        assert!(is_uefi(&root));
+        fs::create_dir_all(under_root(&root, FIRMWARE).join("acpi")).unwrap();
+        assert!(!is_uefi(&root));
+        fs::create_dir_all(under_root(&root, EFI)).unwrap();
+        assert!(is_uefi(&root));
+    }
+
+    #[test]
+    fn reads_setup_mode() {
+        let root = temp_dir("efivars");
+        assert_eq!(setup_mode(&root), None);
+        write_efivar(&root, "SetupMode", 1);
+        write_efivar(&root, "SecureBoot", 0);
+        assert_eq!(setup_mode(&root), Some(true));
+        assert_eq!(secure_boot(&root), Some(false));
+    }
+}
@@ -0,0 +1,60 @@
+// Graphics cards from the PCI devices in /sys
+use std::{fs, path::Path};
+
+use super::under_root;
+use crate::config::Gpu;
+
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+// Display controllers have PCI class 0x03xxxx
+const DISPLAY_CLASS: &str = "0x03";
+
+// The vendors of the display controllers, in bus order
+pub fn load_gpus(root: &Path) -> Vec<Gpu> {
+    let Ok(entries) = fs::read_dir(under_root(root, PCI_DEVICES)) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    devices.sort();
+    devices
+        .iter()
+        .filter(|device| fs::read_to_string(device.join("class")).is_ok_and(|class| class.starts_with(DISPLAY_CLASS)))
+        .filter_map(|device| match fs::read_to_string(device.join("vendor")).ok()?.trim() {
+            "0x10de" => Some(Gpu::Nvidia),
+            "0x1002" => Some(Gpu::Amd),
+            "0x8086" => Some(Gpu::Intel),
+            _ => Some(Gpu::Generic),
+        })
+        .collect()
+}
+
+// A discrete NVIDIA card needs its parameters even next to an integrated GPU
+pub fn preferred(gpus: &[Gpu]) -> Gpu {
+    match gpus.contains(&Gpu::Nvidia) {
+        true => Gpu::Nvidia,
+        false => gpus.first().copied().unwrap_or(Gpu::Generic),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    fn add(root: &Path, slot: &str, class: &str, vendor: &str) {
+        let dir = under_root(root, PCI_DEVICES).join(slot);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("class"), format!("{}\n", class)).unwrap();
+        fs::write(dir.join("vendor"), format!("{}\n", vendor)).unwrap();
+    }
+
+    #[test]
+    fn finds_display_controllers() {
+        let root = temp_dir("gpu");
+        assert_eq!(preferred(&load_gpus(&root)), Gpu::Generic);
+        add(&root, "0000:00:02.0", "0x030000", "0x8086");
+        add(&root, "0000:00:1f.3", "0x040300", "0x8086");
+        add(&root, "0000:01:00.0", "0x030200", "0x10de");
+        assert_eq!(load_gpus(&root), vec![Gpu::Intel, Gpu::Nvidia]);
+        assert_eq!(preferred(&load_gpus(&root)), Gpu::Nvidia);
+    }
+}
@@ -0,0 +1,111 @@
+// Network interfaces from /sys/class/net
+use std::{fs, path::Path};
+
+use super::under_root;
+
+const SYS_NET: &str = "/sys/class/net";
+// ARPHRD_LOOPBACK in the `type` file
+const LOOPBACK_TYPE: &str = "772";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interface {
+    pub name: String,
+    pub wireless: bool,
+    pub mac: String,
+    // operstate is `up`
+    pub up: bool,
+}
+
+impl Interface {
+    // The option shown for it, the name first: `wlan0 (wireless, up, 52:54:00:12:34:56)`
+    pub fn label(&self) -> String {
+        let mut details = Vec::new();
+        if self.wireless {
+            details.push("wireless");
+        }
+        details.push(if self.up { "up" } else { "down" });
+        if !self.mac.is_empty() {
+            details.push(&self.mac);
+        }
+        format!("{} ({})", self.name, details.join(", "))
+    }
+}
+
+// Every interface but loopback, sorted by name
+pub fn load_interfaces(root: &Path) -> Vec<Interface> {
+    let Ok(entries) = fs::read_dir(under_root(root, SYS_NET)) else {
+        return Vec::new();
+    };
+    let mut interfaces: Vec<Interface> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let read = |name: &str| fs::read_to_string(dir.join(name)).map(|value| value.trim().to_string());
+            if read("type").is_ok_and(|kind| kind == LOOPBACK_TYPE) {
+                return None;
+            }
+            Some(Interface {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                wireless: dir.join("wireless").is_dir() || dir.join("phy80211").exists(),
+                mac: read("address").unwrap_or_default(),
+                up: read("operstate").is_ok_and(|state| state == "up"),
+            })
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+// The interface to offer first: one that is up, then a wireless one when Wi-Fi is wanted
+pub fn preferred(interfaces: &[Interface], wireless: bool) -> Option<&Interface> {
+    let rank = |interface: &&Interface| (interface.up, interface.wireless == wireless);
+    interfaces.iter().max_by_key(|interface| (rank(interface), std::cmp::Reverse(interface.name.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    fn add(root: &Path, name: &str, kind: &str, state: &str, wireless: bool) {
+        let dir = under_root(root, SYS_NET).join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("type"), format!("{}\n", kind)).unwrap();
+        fs::write(dir.join("operstate"), format!("{}\n", state)).unwrap();
+        fs::write(dir.join("address"), "52:54:00:12:34:56\n").unwrap();
+        if wireless {
+            fs::create_dir(dir.join("wireless")).unwrap();
+        }
+    }
+
+    #[test]
+    fn reads_interfaces() {
+        let root = temp_dir("interfaces");
+        add(&root, "lo", "772", "unknown", false);
+        add(&root, "wlan0", "1", "down", true);
+        add(&root, "enp1s0", "1", "up", false);
+        let interfaces = load_interfaces(&root);
+        let names: Vec<&str> = interfaces.iter().map(|interface| interface.name.as_str()).collect();
+        assert_eq!(names, vec!["enp1s0", "wlan0"]);
+        assert_eq!(interfaces[1].label(), "wlan0 (wireless, down, 52:54:00:12:34:56)");
+        assert_eq!(preferred(&interfaces, false).unwrap().name, "enp1s0");
+        // A connected cable beats a radio that is down
+        assert_eq!(preferred(&interfaces, true).unwrap().name, "enp1s0");
+    }
+
+    #[test]
+    fn up_interface_wins_over_kind() {
+        let root = temp_dir("interfaces-up");
+        add(&root, "enp1s0", "1", "down", false);
+        add(&root, "wlan0", "1", "up", true);
+        add(&root, "wlan1", "1", "down", true);
+        let interfaces = load_interfaces(&root);
+        assert_eq!(preferred(&interfaces, false).unwrap().name, "wlan0");
+        assert_eq!(preferred(&interfaces, true).unwrap().name, "wlan0");
+    }
+
+    #[test]
+    fn no_sysfs_means_no_interfaces() {
+        assert!(load_interfaces(&temp_dir("nosysfs")).is_empty());
+    }
+}
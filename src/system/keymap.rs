@@ -0,0 +1,151 @@
+// Console keymaps from the kbd package and X11 layouts from xkeyboard-config
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::Path,
+};
+
+use super::under_root;
+
+const KEYMAPS: &str = "/usr/share/kbd/keymaps";
+const XKB_RULES: &str = "/usr/share/X11/xkb/rules/base.lst";
+// Used when the kbd keymaps or the xkb rules are missing
+pub const FALLBACK_KEYMAPS: [&str; 5] = ["us", "de", "fr", "es", "jp106"];
+pub const FALLBACK_LAYOUTS: [&str; 5] = ["us", "de", "fr", "es", "jp"];
+
+// X11/Wayland layouts and their variants, `(layout, variant)`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XkbLayouts {
+    pub layouts: Vec<String>,
+    pub variants: Vec<(String, String)>,
+}
+
+impl XkbLayouts {
+    pub fn fallback() -> Self {
+        XkbLayouts {
+            layouts: FALLBACK_LAYOUTS.iter().map(|layout| layout.to_string()).collect(),
+            variants: Vec::new(),
+        }
+    }
+
+    // The variants of one layout
+    pub fn variants_of(&self, layout: &str) -> Vec<String> {
+        self.variants
+            .iter()
+            .filter(|(variant_layout, _)| variant_layout == layout)
+            .map(|(_, variant)| variant.clone())
+            .collect()
+    }
+}
+
+// Every console keymap name (`de-latin1`), sorted.
+// The files are `<keymaps>/<arch>/<type>/<name>.map.gz`, `include` dirs only hold fragments.
+pub fn load_keymaps(root: &Path) -> Vec<String> {
+    let mut keymaps = BTreeSet::new();
+    walk(&under_root(root, KEYMAPS), &mut keymaps);
+    keymaps.into_iter().collect()
+}
+
+fn walk(dir: &Path, keymaps: &mut BTreeSet<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => {
+                if file_name != "include" {
+                    walk(&entry.path(), keymaps);
+                }
+            }
+            Ok(_) => {
+                let name = file_name.strip_suffix(".gz").unwrap_or(&file_name);
+                if let Some(name) = name.strip_suffix(".map") {
+                    keymaps.insert(name.to_string());
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+// KEYMAP from /etc/vconsole.conf
+pub fn current_keymap(root: &Path) -> Option<String> {
+    let conf = fs::read_to_string(under_root(root, "/etc/vconsole.conf")).ok()?;
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("KEYMAP="))
+        .map(|keymap| keymap.trim_matches('"').to_string())
+        .find(|keymap| !keymap.is_empty())
+}
+
+// Layouts and variants from the `! layout` and `! variant` sections of base.lst
+pub fn load_xkb_layouts(root: &Path) -> XkbLayouts {
+    let Ok(rules) = fs::read_to_string(under_root(root, XKB_RULES)) else {
+        return XkbLayouts::default();
+    };
+    let mut xkb = XkbLayouts::default();
+    let mut section = "";
+    for line in rules.lines() {
+        if let Some(name) = line.strip_prefix('!') {
+            section = name.trim();
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            continue;
+        };
+        match section {
+            "layout" => xkb.layouts.push(name.to_string()),
+            // `  chr             us: Cherokee`
+            "variant" => {
+                if let Some(layout) = words.next().and_then(|word| word.strip_suffix(':')) {
+                    xkb.variants.push((layout.to_string(), name.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    xkb
+}
+
+// The xkb layout matching a console keymap, `de-latin1-nodeadkeys` -> `de`
+pub fn layout_for_keymap(keymap: &str, layouts: &[String]) -> Option<String> {
+    let prefix = keymap.split(['-', '_']).next()?;
+    layouts.iter().find(|layout| *layout == prefix).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    #[test]
+    fn finds_keymaps_but_not_includes() {
+        let root = temp_dir("keymaps");
+        let keymaps = under_root(&root, KEYMAPS);
+        fs::create_dir_all(keymaps.join("i386/qwertz")).unwrap();
+        fs::create_dir_all(keymaps.join("i386/include")).unwrap();
+        fs::write(keymaps.join("i386/qwertz/de-latin1.map.gz"), "").unwrap();
+        fs::write(keymaps.join("i386/qwertz/sg.map"), "").unwrap();
+        fs::write(keymaps.join("i386/include/qwertz-layout.inc"), "").unwrap();
+        fs::write(keymaps.join("i386/include/euro.map"), "").unwrap();
+        assert_eq!(load_keymaps(&root), vec!["de-latin1", "sg"]);
+    }
+
+    #[test]
+    fn reads_xkb_rules() {
+        let root = temp_dir("xkb");
+        let rules = under_root(&root, XKB_RULES);
+        fs::create_dir_all(rules.parent().unwrap()).unwrap();
+        fs::write(
+            &rules,
+            "! model\n  pc105  Generic 105-key PC\n\n! layout\n  us   English (US)\n  de   German\n\n! variant\n  chr  us: Cherokee\n  nodeadkeys  de: German (no dead keys)\n\n! option\n  grp  Switching\n",
+        )
+        .unwrap();
+        let xkb = load_xkb_layouts(&root);
+        assert_eq!(xkb.layouts, vec!["us", "de"]);
+        assert_eq!(xkb.variants_of("de"), vec!["nodeadkeys"]);
+        assert_eq!(layout_for_keymap("de-latin1", &xkb.layouts).as_deref(), Some("de"));
+        assert_eq!(layout_for_keymap("sg", &xkb.layouts), None);
+    }
+}
@@ -0,0 +1,100 @@
+// Locales glibc can generate, from the SUPPORTED list or /etc/locale.gen
+use std::{fs, path::Path};
+
+use super::under_root;
+
+const SUPPORTED: &str = "/usr/share/i18n/SUPPORTED";
+const LOCALE_GEN: &str = "/etc/locale.gen";
+// Used when neither file exists
+pub const FALLBACK_LOCALES: [&str; 5] =
+    ["en_US.UTF-8 UTF-8", "de_DE.UTF-8 UTF-8", "fr_FR.UTF-8 UTF-8", "ja_JP.UTF-8 UTF-8", "zh_CN.UTF-8 UTF-8"];
+
+// Every locale as a locale.gen line (`en_US.UTF-8 UTF-8`), in file order
+pub fn load_locales(root: &Path) -> Vec<String> {
+    if let Ok(supported) = fs::read_to_string(under_root(root, SUPPORTED)) {
+        let locales = parse_supported(&supported);
+        if !locales.is_empty() {
+            return locales;
+        }
+    }
+    fs::read_to_string(under_root(root, LOCALE_GEN))
+        .map(|locale_gen| parse_locale_gen(&locale_gen))
+        .unwrap_or_default()
+}
+
+// SUPPORTED lines end with a ` \` continuation left over from the makefile
+fn parse_supported(supported: &str) -> Vec<String> {
+    supported
+        .lines()
+        .map(|line| line.trim_end_matches('\\').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && line.contains(' '))
+        .filter(|line| !line.starts_with("SUPPORTED-LOCALES="))
+        .map(String::from)
+        .collect()
+}
+
+// locale.gen lists every locale commented out, except the enabled ones.
+// Only `#name charset` lines are locales, the header comments have more words,
+// the header examples repeat real entries so duplicates are dropped.
+fn parse_locale_gen(locale_gen: &str) -> Vec<String> {
+    let mut locales: Vec<String> = Vec::new();
+    for line in locale_gen.lines() {
+        let line = line.trim_start_matches('#').trim();
+        if line.split_whitespace().count() != 2 || !(line.contains('_') || line.starts_with("C.")) {
+            continue;
+        }
+        if !locales.iter().any(|locale| locale == line) {
+            locales.push(line.to_string());
+        }
+    }
+    locales
+}
+
+// The locale name of a locale.gen line, `en_US.UTF-8 UTF-8` -> `en_US.UTF-8`
+pub fn name_of(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or(line)
+}
+
+// LANG from /etc/locale.conf
+pub fn current_lang(root: &Path) -> Option<String> {
+    let conf = fs::read_to_string(under_root(root, "/etc/locale.conf")).ok()?;
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("LANG="))
+        .map(|lang| lang.trim_matches('"').to_string())
+        .find(|lang| !lang.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    #[test]
+    fn reads_supported_then_locale_gen() {
+        let root = temp_dir("locales");
+        fs::create_dir_all(root.join("etc")).unwrap();
+        fs::write(
+            root.join("etc/locale.gen"),
+            "# Configuration file for locale-gen\n#\n#  en_US.UTF-8 UTF-8\n#de_DE.UTF-8 UTF-8\nen_US.UTF-8 UTF-8\n",
+        )
+        .unwrap();
+        assert_eq!(load_locales(&root), vec!["en_US.UTF-8 UTF-8", "de_DE.UTF-8 UTF-8"]);
+
+        fs::create_dir_all(root.join("usr/share/i18n")).unwrap();
+        fs::write(
+            root.join("usr/share/i18n/SUPPORTED"),
+            "aa_DJ.UTF-8 UTF-8 \\\naa_DJ ISO-8859-1 \\\nsr_RS@latin UTF-8 \\\n",
+        )
+        .unwrap();
+        assert_eq!(load_locales(&root), vec!["aa_DJ.UTF-8 UTF-8", "aa_DJ ISO-8859-1", "sr_RS@latin UTF-8"]);
+    }
+
+    #[test]
+    fn reads_lang_from_locale_conf() {
+        let root = temp_dir("localeconf");
+        fs::create_dir_all(root.join("etc")).unwrap();
+        fs::write(root.join("etc/locale.conf"), "LC_TIME=C\nLANG=\"de_DE.UTF-8\"\n").unwrap();
+        assert_eq!(current_lang(&root).as_deref(), Some("de_DE.UTF-8"));
+        assert_eq!(name_of("de_DE.UTF-8 UTF-8"), "de_DE.UTF-8");
+    }
+}
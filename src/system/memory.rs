@@ -0,0 +1,29 @@
+// The installed RAM from /proc/meminfo
+use std::{fs, path::Path};
+
+use super::under_root;
+
+const MEMINFO: &str = "/proc/meminfo";
+
+// MemTotal in bytes, the line reads `MemTotal:       16318412 kB`
+pub fn total_memory(root: &Path) -> Option<u64> {
+    let meminfo = fs::read_to_string(under_root(root, MEMINFO)).ok()?;
+    let line = meminfo.lines().find_map(|line| line.strip_prefix("MemTotal:"))?;
+    let kib: u64 = line.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib << 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    #[test]
+    fn reads_mem_total() {
+        let root = temp_dir("memory");
+        assert_eq!(total_memory(&root), None);
+        fs::create_dir_all(root.join("proc")).unwrap();
+        fs::write(under_root(&root, MEMINFO), "MemTotal:        8388608 kB\nMemFree:         1024 kB\n").unwrap();
+        assert_eq!(total_memory(&root), Some(8 << 30));
+    }
+}
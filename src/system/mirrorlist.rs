@@ -0,0 +1,37 @@
+// The full mirrorlist shipped by pacman-mirrorlist, for picking mirrors offline
+use std::{fs, path::Path};
+
+use super::under_root;
+use crate::mirrors;
+
+// The package's copy, kept as .pacnew once reflector has rewritten the list
+const MIRRORLISTS: [&str; 2] = ["/etc/pacman.d/mirrorlist.pacnew", "/etc/pacman.d/mirrorlist"];
+
+// The first list with `## Country` sections, a reflector output has none
+pub fn load_mirrorlist(root: &Path) -> Option<String> {
+    MIRRORLISTS
+        .iter()
+        .filter_map(|path| fs::read_to_string(under_root(root, path)).ok())
+        .find(|list| {
+            list.lines()
+                .filter_map(|line| line.strip_prefix("## "))
+                .any(|name| mirrors::code_for_name(name.trim()).is_some())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+
+    #[test]
+    fn skips_lists_without_countries() {
+        let root = temp_dir("mirrorlist");
+        let dir = under_root(&root, "/etc/pacman.d");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("mirrorlist"), "## Generated by reflector\n## When: 2024-01-01\nServer = https://a/\n").unwrap();
+        assert_eq!(load_mirrorlist(&root), None);
+        fs::write(dir.join("mirrorlist.pacnew"), "## Canada\n#Server = https://b/\n").unwrap();
+        assert_eq!(load_mirrorlist(&root).as_deref(), Some("## Canada\n#Server = https://b/\n"));
+    }
+}
@@ -0,0 +1,141 @@
+// What the menu can find out about the machine it runs on.
+// Every lookup takes the filesystem root so tests can point it at a fixture tree.
+pub mod disks;
+pub mod firmware;
+pub mod gpu;
+pub mod interfaces;
+pub mod keymap;
+pub mod locale;
+pub mod memory;
+pub mod mirrorlist;
+pub mod pacman;
+pub mod timezone;
+
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::config::Gpu;
+use interfaces::Interface;
+use keymap::XkbLayouts;
+use pacman::PackageDb;
+
+// Everything detected at startup, used to build the questions
+#[derive(Clone, Debug)]
+pub struct SystemInfo {
+    pub timezones: Vec<String>,
+    pub current_timezone: Option<String>,
+    // locale.gen lines, `en_US.UTF-8 UTF-8`
+    pub locales: Vec<String>,
+    pub current_lang: Option<String>,
+    pub keymaps: Vec<String>,
+    pub current_keymap: Option<String>,
+    pub xkb: XkbLayouts,
+    // The sync databases, None skips checking package names
+    pub packages: Option<Rc<PackageDb>>,
+    // The full mirrorlist with country sections, for picking mirrors offline
+    pub mirrorlist: Option<Rc<str>>,
+    // Empty when /sys isn't there, the interface is then typed in
+    pub interfaces: Vec<Interface>,
+    // A fixed disk that doesn't spin, fstrim is then suggested
+    pub has_ssd: bool,
+    // RAM in bytes, hibernation needs as much swap
+    pub memory: Option<u64>,
+    // Display controller vendors, the GPU question defaults from them
+    pub gpus: Vec<Gpu>,
+    // Booted through UEFI, systemd-boot needs it
+    pub uefi: bool,
+    // From the efivars, None when they can't be read
+    pub setup_mode: Option<bool>,
+    pub secure_boot: Option<bool>,
+}
+
+impl SystemInfo {
+    // Look everything up below `root`, falling back to built-in lists
+    pub fn detect(root: &Path) -> Self {
+        let mut info = SystemInfo::default();
+        let timezones = timezone::load_zones(root);
+        if !timezones.is_empty() {
+            info.timezones = timezones;
+        }
+        info.current_timezone = timezone::current_zone(root);
+        let locales = locale::load_locales(root);
+        if !locales.is_empty() {
+            info.locales = locales;
+        }
+        info.current_lang = locale::current_lang(root);
+        let keymaps = keymap::load_keymaps(root);
+        if !keymaps.is_empty() {
+            info.keymaps = keymaps;
+        }
+        info.current_keymap = keymap::current_keymap(root);
+        let xkb = keymap::load_xkb_layouts(root);
+        if !xkb.layouts.is_empty() {
+            info.xkb = xkb;
+        }
+        info.packages = PackageDb::load(root).map(Rc::new);
+        info.mirrorlist = mirrorlist::load_mirrorlist(root).map(Rc::from);
+        info.interfaces = interfaces::load_interfaces(root);
+        info.has_ssd = disks::has_ssd(root);
+        info.memory = memory::total_memory(root);
+        info.gpus = gpu::load_gpus(root);
+        info.uefi = firmware::is_uefi(root);
+        info.setup_mode = firmware::setup_mode(root);
+        info.secure_boot = firmware::secure_boot(root);
+        log_info!(
+            "detected {} timezones, current timezone {:?}",
+            info.timezones.len(),
+            info.current_timezone
+        );
+        log_info!("detected {} locales, current LANG {:?}", info.locales.len(), info.current_lang);
+        log_info!(
+            "detected {} keymaps, current keymap {:?}, {} xkb layouts",
+            info.keymaps.len(),
+            info.current_keymap,
+            info.xkb.layouts.len()
+        );
+        match &info.packages {
+            Some(packages) => log_info!("read {} packages from the pacman sync databases", packages.len()),
+            None => log_warn!("no pacman sync databases, package names won't be checked"),
+        }
+        let names: Vec<String> = info.interfaces.iter().map(Interface::label).collect();
+        log_info!("detected network interfaces: {}", names.join("; "));
+        log_info!("SSD detected: {}, memory {:?} bytes", info.has_ssd, info.memory);
+        log_info!("detected GPUs: {:?}, UEFI: {}", info.gpus, info.uefi);
+        log_info!("Secure Boot: {:?}, setup mode: {:?}", info.secure_boot, info.setup_mode);
+        if info.mirrorlist.is_none() {
+            log_info!("no mirrorlist with country sections, offline mirrors not offered");
+        }
+        info
+    }
+}
+
+impl Default for SystemInfo {
+    // The lists used when nothing could be detected
+    fn default() -> Self {
+        SystemInfo {
+            timezones: timezone::FALLBACK_ZONES.iter().map(|zone| zone.to_string()).collect(),
+            current_timezone: None,
+            locales: locale::FALLBACK_LOCALES.iter().map(|locale| locale.to_string()).collect(),
+            current_lang: None,
+            keymaps: keymap::FALLBACK_KEYMAPS.iter().map(|keymap| keymap.to_string()).collect(),
+            current_keymap: None,
+            xkb: XkbLayouts::fallback(),
+            packages: None,
+            mirrorlist: None,
+            interfaces: Vec::new(),
+            has_ssd: false,
+            memory: None,
+            gpus: Vec::new(),
+            uefi: true,
+            setup_mode: None,
+            secure_boot: None,
+        }
+    }
+}
+
+// Join an absolute system path onto the root, `/etc/localtime` -> `<root>/etc/localtime`
+pub fn under_root(root: &Path, path: &str) -> PathBuf {
+    root.join(path.trim_start_matches('/'))
+}
@@ -0,0 +1,209 @@
+// Package names and descriptions from the pacman sync databases.
+// Each `<repo>.db` is a gzipped tar with a `<name>-<version>/desc` file per package.
+use flate2::read::GzDecoder;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+use super::under_root;
+
+const SYNC_DIR: &str = "/var/lib/pacman/sync";
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackageDb {
+    // Package name -> description
+    packages: BTreeMap<String, String>,
+    // Group names (`gnome`) and virtual packages (`sh`) pacman also accepts
+    groups: BTreeSet<String>,
+    provides: BTreeSet<String>,
+}
+
+impl PackageDb {
+    // Read every sync database, None when there are none (not running on Arch)
+    pub fn load(root: &Path) -> Option<PackageDb> {
+        let mut db = PackageDb::default();
+        let mut entries: Vec<_> = fs::read_dir(under_root(root, SYNC_DIR))
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "db"))
+            .collect();
+        entries.sort();
+        for path in entries {
+            if let Err(e) = db.read_repo(&path) {
+                log_warn!("can't read pacman database {}: {}", path.display(), e);
+            }
+        }
+        (!db.packages.is_empty()).then_some(db)
+    }
+
+    fn read_repo(&mut self, path: &Path) -> io::Result<()> {
+        let mut file = File::open(path)?;
+        let mut magic = [0u8; 2];
+        file.read_exact(&mut magic)?;
+        if magic != GZIP_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "only gzip databases are supported"));
+        }
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(path)?));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.path()?.ends_with("desc") {
+                continue;
+            }
+            let mut desc = String::new();
+            entry.read_to_string(&mut desc)?;
+            self.add_desc(&desc);
+        }
+        Ok(())
+    }
+
+    // Parse one `desc` file, `%FIELD%` headers each followed by lines of values
+    fn add_desc(&mut self, desc: &str) {
+        let mut fields: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        let mut field = "";
+        for line in desc.lines() {
+            if line.starts_with('%') && line.ends_with('%') && line.len() > 2 {
+                field = &line[1..line.len() - 1];
+            } else if !line.is_empty() {
+                fields.entry(field).or_default().push(line);
+            }
+        }
+        let Some(name) = fields.get("NAME").and_then(|names| names.first()) else {
+            return;
+        };
+        let description = fields.get("DESC").and_then(|desc| desc.first()).copied().unwrap_or("");
+        self.packages.insert(name.to_string(), description.to_string());
+        for group in fields.get("GROUPS").into_iter().flatten() {
+            self.groups.insert(group.to_string());
+        }
+        // `sh=5.2` provides `sh`
+        for provided in fields.get("PROVIDES").into_iter().flatten() {
+            let provided = provided.split(['=', '<', '>']).next().unwrap_or(provided);
+            self.provides.insert(provided.to_string());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    // True when pacman can install something by this name
+    pub fn contains(&self, name: &str) -> bool {
+        self.packages.contains_key(name) || self.groups.contains(name) || self.provides.contains(name)
+    }
+
+    pub fn description(&self, name: &str) -> Option<&str> {
+        match self.packages.get(name) {
+            Some(description) => Some(description),
+            None if self.groups.contains(name) => Some("package group"),
+            None => None,
+        }
+    }
+
+    // Up to three package names close to a misspelt one, closest first
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        let mut close: Vec<(usize, &String)> = self
+            .packages
+            .keys()
+            .map(|candidate| (distance(name, candidate), candidate))
+            .filter(|(distance, candidate)| *distance <= 2.max(name.len() / 4) || candidate.starts_with(name))
+            .collect();
+        close.sort();
+        close.into_iter().take(3).map(|(_, candidate)| candidate.clone()).collect()
+    }
+}
+
+impl PackageDb {
+    // Refuse the first name pacman wouldn't know, suggesting what might have been meant
+    pub fn check_names(&self, names: &[String]) -> Result<(), String> {
+        let Some(unknown) = names.iter().find(|name| !self.contains(name)) else {
+            return Ok(());
+        };
+        let suggestions = self.suggest(unknown);
+        if suggestions.is_empty() {
+            Err(format!("Unknown package '{}'", unknown))
+        } else {
+            Err(format!("Unknown package '{}', did you mean {}?", unknown, suggestions.join(", ")))
+        }
+    }
+}
+
+// Levenshtein distance between two names
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+    use flate2::{write::GzEncoder, Compression};
+    use std::path::PathBuf;
+
+    // Write a sync database holding `(name, description, groups)` packages below `root`
+    pub fn write_fixture_db(root: &Path, repo: &str, packages: &[(&str, &str, &[&str])]) -> PathBuf {
+        let dir = under_root(root, SYNC_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.db", repo));
+        let mut builder = tar::Builder::new(GzEncoder::new(File::create(&path).unwrap(), Compression::default()));
+        for (name, description, groups) in packages {
+            let mut desc = format!("%FILENAME%\n{0}-1.0-1-x86_64.pkg.tar.zst\n\n%NAME%\n{0}\n\n%VERSION%\n1.0-1\n\n%DESC%\n{1}\n\n", name, description);
+            if !groups.is_empty() {
+                desc.push_str(&format!("%GROUPS%\n{}\n\n", groups.join("\n")));
+            }
+            desc.push_str("%PROVIDES%\nsh=5.2\n\n");
+            let mut header = tar::Header::new_gnu();
+            header.set_size(desc.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("{}-1.0-1/desc", name), desc.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_a_sync_database() {
+        let root = temp_dir("pacman");
+        write_fixture_db(
+            &root,
+            "extra",
+            &[("vim", "Vi Improved", &[]), ("htop", "Interactive process viewer", &[]), ("gdm", "Display manager", &["gnome"])],
+        );
+        fs::write(under_root(&root, SYNC_DIR).join("broken.db"), "not a database").unwrap();
+        let db = PackageDb::load(&root).unwrap();
+        assert_eq!(db.len(), 3);
+        assert!(db.contains("vim") && db.contains("gnome") && db.contains("sh"));
+        assert!(!db.contains("vmi"));
+        assert_eq!(db.description("htop"), Some("Interactive process viewer"));
+        assert_eq!(db.suggest("vmi"), vec!["vim"]);
+        assert_eq!(db.suggest("hto"), vec!["htop"]);
+        assert_eq!(db.check_names(&["vim".into(), "gnome".into()]), Ok(()));
+        assert_eq!(
+            db.check_names(&["vim".into(), "vmi".into()]),
+            Err("Unknown package 'vmi', did you mean vim?".to_string())
+        );
+        assert_eq!(db.check_names(&["zzzzzz".into()]), Err("Unknown package 'zzzzzz'".to_string()));
+    }
+
+    #[test]
+    fn no_databases_means_no_check() {
+        assert_eq!(PackageDb::load(&temp_dir("nopacman")), None);
+    }
+}
@@ -0,0 +1,151 @@
+// Timezones from the tzdata zoneinfo database
+use std::{collections::BTreeSet, fs, path::Path};
+
+use super::under_root;
+
+const ZONEINFO: &str = "/usr/share/zoneinfo";
+// Used when the zoneinfo database is missing
+pub const FALLBACK_ZONES: [&str; 5] = ["UTC", "America/New_York", "Europe/London", "Asia/Tokyo", "Australia/Sydney"];
+// Top level zoneinfo directories holding real `Region/City` zones,
+// the others are legacy aliases (`US/Eastern`) or other formats (`posix/`)
+const REGIONS: [&str; 10] = [
+    "Africa", "America", "Antarctica", "Arctic", "Asia", "Atlantic", "Australia", "Europe", "Indian", "Pacific",
+];
+
+// Every `Region/City` zone, sorted, plus UTC.
+// Reads zone1970.tab and falls back to walking the zoneinfo tree.
+pub fn load_zones(root: &Path) -> Vec<String> {
+    let zoneinfo = under_root(root, ZONEINFO);
+    let mut zones = BTreeSet::new();
+    if let Ok(table) = fs::read_to_string(zoneinfo.join("zone1970.tab")) {
+        zones.extend(parse_zone_tab(&table));
+    }
+    if zones.is_empty() {
+        for region in REGIONS {
+            walk(&zoneinfo.join(region), region, &mut zones);
+        }
+    }
+    if zones.is_empty() {
+        return Vec::new();
+    }
+    zones.insert("UTC".to_string());
+    zones.into_iter().collect()
+}
+
+// The zone names from zone1970.tab, the third column of each line
+fn parse_zone_tab(table: &str) -> Vec<String> {
+    table
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split('\t').nth(2))
+        .map(|zone| zone.trim().to_string())
+        .filter(|zone| !zone.is_empty())
+        .collect()
+}
+
+// Collect the zone files below a region directory
+fn walk(dir: &Path, name: &str, zones: &mut BTreeSet<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let zone = format!("{}/{}", name, file_name);
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => walk(&entry.path(), &zone, zones),
+            Ok(_) => {
+                zones.insert(zone);
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+// The timezone of the running system, from the /etc/localtime link or /etc/timezone
+pub fn current_zone(root: &Path) -> Option<String> {
+    if let Ok(target) = fs::read_link(under_root(root, "/etc/localtime")) {
+        let target = target.to_string_lossy().into_owned();
+        if let Some((_, zone)) = target.split_once("zoneinfo/") {
+            return Some(normalize(zone));
+        }
+    }
+    let zone = fs::read_to_string(under_root(root, "/etc/timezone")).ok()?;
+    let zone = zone.trim();
+    (!zone.is_empty()).then(|| normalize(zone))
+}
+
+// `Etc/UTC` and friends are all plain UTC
+fn normalize(zone: &str) -> String {
+    match zone {
+        "Etc/UTC" | "Etc/UCT" | "Etc/Universal" | "Etc/Zulu" | "UCT" | "Universal" | "Zulu" => "UTC".to_string(),
+        _ => zone.to_string(),
+    }
+}
+
+// The regions of a zone list, `Europe/London` -> `Europe`, zones without a city are kept whole
+pub fn regions(zones: &[String]) -> Vec<String> {
+    let regions: BTreeSet<&str> = zones.iter().map(|zone| region_of(zone)).collect();
+    regions.into_iter().map(String::from).collect()
+}
+
+// The cities of one region, `America/Argentina/Salta` -> `Argentina/Salta`
+pub fn cities(zones: &[String], region: &str) -> Vec<String> {
+    zones
+        .iter()
+        .filter_map(|zone| zone.split_once('/'))
+        .filter(|(zone_region, _)| *zone_region == region)
+        .map(|(_, city)| city.to_string())
+        .collect()
+}
+
+pub fn region_of(zone: &str) -> &str {
+    zone.split('/').next().unwrap_or(zone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::temp_dir;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn reads_zone1970_tab() {
+        let root = temp_dir("zones");
+        let zoneinfo = under_root(&root, ZONEINFO);
+        fs::create_dir_all(&zoneinfo).unwrap();
+        fs::write(
+            zoneinfo.join("zone1970.tab"),
+            "# comment\nAD\t+4230+00131\tEurope/Andorra\nAR\t-3436-05827\tAmerica/Argentina/Buenos_Aires\tBuenos Aires (BA, CF)\n",
+        )
+        .unwrap();
+        let zones = load_zones(&root);
+        assert_eq!(zones, vec!["America/Argentina/Buenos_Aires", "Europe/Andorra", "UTC"]);
+        assert_eq!(regions(&zones), vec!["America", "Europe", "UTC"]);
+        assert_eq!(cities(&zones, "America"), vec!["Argentina/Buenos_Aires"]);
+    }
+
+    #[test]
+    fn walks_the_tree_without_a_table() {
+        let root = temp_dir("zonetree");
+        let zoneinfo = under_root(&root, ZONEINFO);
+        fs::create_dir_all(zoneinfo.join("Asia")).unwrap();
+        fs::create_dir_all(zoneinfo.join("posix/Asia")).unwrap();
+        fs::write(zoneinfo.join("Asia/Tokyo"), "").unwrap();
+        fs::write(zoneinfo.join("posix/Asia/Tokyo"), "").unwrap();
+        assert_eq!(load_zones(&root), vec!["Asia/Tokyo", "UTC"]);
+    }
+
+    #[test]
+    fn detects_the_current_zone() {
+        let root = temp_dir("localtime");
+        fs::create_dir_all(root.join("etc")).unwrap();
+        symlink("/usr/share/zoneinfo/Europe/Paris", root.join("etc/localtime")).unwrap();
+        assert_eq!(current_zone(&root).as_deref(), Some("Europe/Paris"));
+
+        let root = temp_dir("etctimezone");
+        fs::create_dir_all(root.join("etc")).unwrap();
+        fs::write(root.join("etc/timezone"), "Etc/UTC\n").unwrap();
+        assert_eq!(current_zone(&root).as_deref(), Some("UTC"));
+        assert_eq!(current_zone(&temp_dir("nothing")), None);
+    }
+}
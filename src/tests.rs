@@ -0,0 +1,656 @@
+// Whole app tests: scripted key presses against ratatui's TestBackend,
+// with rendered screens compared to the snapshots in tests/snapshots and
+// generated files to tests/golden.
+// Run with UPDATE_SNAPSHOTS=1 to rewrite both after an intended change.
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+    Terminal,
+};
+use std::{
+    env, fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    app::{self, App, Screen},
+    config::{
+        ArchConfig, AurConfig, AurHelper, DesktopConfig, DisplayManager, Gpu, GrubTarget, KernelConfig, KeyboardConfig,
+        LocaleConfig, MirrorConfig, MirrorSort, NetworkConfig, RootLogin, SecureBootConfig, ServicesConfig, Session,
+        StorageConfig, SwapConfig, SwapKind, SystemdBootConfig,
+    },
+    event::{EventSource, ScriptedEvents},
+    run_app,
+    size::Size,
+    ssh::tests::ED25519,
+    system::{firmware::tests::write_efivar, interfaces::Interface, pacman::tests::write_fixture_db, SystemInfo},
+    theme::Theme,
+    ui,
+};
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+
+// A fresh directory under the system temp dir
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = env::temp_dir().join(format!(
+        "arch-menu-{}-{}-{}",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Keys answering every question of the default wizard
+fn answer_everything(events: ScriptedEvents) -> ScriptedEvents {
+    events
+        .key(KeyCode::Enter) // leave the splash screen
+        .text("archbox")
+        .key(KeyCode::Enter)
+        .text("sam")
+        .key(KeyCode::Enter)
+        .text("hunter2")
+        .key(KeyCode::Enter)
+        .text("vid ")
+        .key(KeyCode::Enter)
+        .text("asia")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        // Locales: en_US is ticked, add de_DE and make it LANG, LC_TIME stays English
+        .key(KeyCode::Down)
+        .key(KeyCode::Char(' '))
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        .text("TIME ")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Up)
+        .key(KeyCode::Enter)
+        // Console keymap fr, the X11 layout follows, no variants in the fallback list
+        .text("fr")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        // ext4 has no compression level, no encryption, EFI partition of 512M
+        .key(KeyCode::Enter)
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Backspace)
+        .text("512M")
+        .key(KeyCode::Enter)
+        // zram at half the RAM with zstd
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        // paru built by sam, then systemd-boot
+        .key(KeyCode::Down)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .text("spotify")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        // linux-lts next to linux and booted by default, with the NVIDIA parameters
+        .key(KeyCode::Down)
+        .key(KeyCode::Char(' '))
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .text("nowatchdog")
+        .key(KeyCode::Enter)
+        // systemd-boot waits 3 seconds, editor locked, no UKI and no Secure Boot
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        // The base-devel group, git is in it already
+        .text("base ")
+        .key(KeyCode::Enter)
+        .text("git, htop vim")
+        .key(KeyCode::Enter)
+        // KDE with its defaults, sddm and Wayland, plus KDE Connect
+        .text("kde")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Char(' '))
+        .key(KeyCode::Enter)
+        // Mirrors in Canada and Germany sorted by score, the rest as offered
+        .text("canada ")
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Backspace)
+        .text("germany ")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        // NetworkManager on eth0 with a static address, no Wi-Fi
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        .text("192.168.1.10/24")
+        .key(KeyCode::Enter)
+        .text("192.168.1.1")
+        .key(KeyCode::Enter)
+        .text("1.1.1.1")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Down)
+        .key(KeyCode::Enter)
+        // The suggested services plus printing, none kept off
+        .text("cups ")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter)
+}
+
+fn expected_config() -> ArchConfig {
+    ArchConfig {
+        hostname: "archbox".to_string(),
+        username: "sam".to_string(),
+        password: "hunter2".to_string(),
+        timezone: "Asia/Tokyo".to_string(),
+        locale: LocaleConfig {
+            lang: "de_DE.UTF-8".to_string(),
+            enabled: vec!["en_US.UTF-8 UTF-8".to_string(), "de_DE.UTF-8 UTF-8".to_string()],
+            overrides: [("LC_TIME".to_string(), "en_US.UTF-8".to_string())].into_iter().collect(),
+        },
+        keyboard: KeyboardConfig {
+            console_keymap: "fr".to_string(),
+            xkb_layout: "fr".to_string(),
+            xkb_variant: String::new(),
+        },
+        format_type: "ext4".to_string(),
+        bootloader: "systemd-boot".to_string(),
+        enable_ssh: false,
+        user_groups: vec!["wheel".to_string(), "video".to_string()],
+        package_groups: vec!["base-devel".to_string()],
+        packages: ["base-devel", "git", "htop", "vim"].iter().map(|p| p.to_string()).collect(),
+        storage: StorageConfig {
+            efi_size: Size::Bytes(512 << 20),
+            btrfs_compression_level: None,
+            encrypt: false,
+        },
+        swap: SwapConfig::default(),
+        kernel: KernelConfig {
+            kernels: vec!["linux".to_string(), "linux-lts".to_string()],
+            default: "linux-lts".to_string(),
+            gpu: Gpu::Nvidia,
+            params: vec!["nowatchdog".to_string()],
+            ..KernelConfig::default()
+        },
+        mirrors: MirrorConfig {
+            countries: vec!["CA".to_string(), "DE".to_string()],
+            sort: MirrorSort::Score,
+            ..MirrorConfig::default()
+        },
+        network: NetworkConfig {
+            dhcp: false,
+            address: "192.168.1.10/24".to_string(),
+            gateway: "192.168.1.1".to_string(),
+            dns: vec!["1.1.1.1".to_string()],
+            ..NetworkConfig::default()
+        },
+        services: ServicesConfig {
+            enable: ["systemd-timesyncd.service", "reflector.timer", "paccache.timer", "bluetooth.service", "cups.service"]
+                .iter()
+                .map(|unit| unit.to_string())
+                .collect(),
+            disable: Vec::new(),
+        },
+        grub: None,
+        systemd_boot: Some(SystemdBootConfig::default()),
+        secure_boot: None,
+        ssh: None,
+        desktop: Some(DesktopConfig {
+            profile: "kde".to_string(),
+            display_manager: DisplayManager::Sddm,
+            session: Session::Wayland,
+            extras: vec!["kdeconnect".to_string()],
+            packages: ["plasma-meta", "konsole", "dolphin", "sddm", "kdeconnect"].iter().map(|p| p.to_string()).collect(),
+        }),
+        aur: Some(AurConfig {
+            helper: AurHelper::Paru,
+            build_user: "sam".to_string(),
+            packages: vec!["spotify".to_string()],
+        }),
+    }
+}
+
+// Run the app to completion, returning the final state and terminal
+fn run_script(app: &mut App, mut events: ScriptedEvents) -> Terminal<TestBackend> {
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    run_app(&mut terminal, app, &mut events).unwrap();
+    assert!(events.is_empty(), "app quit before the script ended");
+    terminal
+}
+
+// Accept the defaults until the question with this prompt comes up
+fn skip_to(mut app: App, prompt: &str) -> App {
+    while app.question().unwrap().prompt != prompt {
+        app::handle_key(&mut app, KeyEvent::from(KeyCode::Enter));
+    }
+    app
+}
+
+// Press keys on an app that is already running
+fn press(mut app: App, keys: ScriptedEvents) -> App {
+    let mut keys = keys;
+    while !keys.is_empty() {
+        if let Event::Key(key) = keys.read().unwrap() {
+            app::handle_key(&mut app, key);
+        }
+    }
+    app
+}
+
+// A fresh app past the splash screen that checks package names against a small sync database
+fn app_with_packages() -> App {
+    let root = temp_dir("syncdb");
+    write_fixture_db(
+        &root,
+        "extra",
+        &[("htop", "Interactive process viewer", &[]), ("vim", "Vi Improved, a highly configurable text editor", &[])],
+    );
+    let system = SystemInfo::detect(&root);
+    press(App::new(PathBuf::from("/tmp/arch_config.toml"), &system), ScriptedEvents::new().key(KeyCode::Enter))
+}
+
+// Drive a fresh app with key presses, without a terminal
+fn app_after(keys: ScriptedEvents) -> App {
+    press(App::new(PathBuf::from("/tmp/arch_config.toml"), &SystemInfo::default()), keys)
+}
+
+fn render(app: &App) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    terminal.draw(|f| ui::draw(app, f)).unwrap();
+    terminal.backend().buffer().clone()
+}
+
+// The buffer as text, followed by a map of the styles used in each cell
+fn snapshot(buffer: &Buffer) -> String {
+    const KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let width = buffer.area.width as usize;
+    let mut styles: Vec<(Color, Color, Modifier)> = Vec::new();
+    let mut text = String::new();
+    let mut style_map = String::new();
+    for (i, cell) in buffer.content.iter().enumerate() {
+        let style = (cell.fg, cell.bg, cell.modifier);
+        let index = match styles.iter().position(|s| *s == style) {
+            Some(index) => index,
+            None => {
+                styles.push(style);
+                styles.len() - 1
+            }
+        };
+        text.push_str(&cell.symbol);
+        style_map.push(KEYS.chars().nth(index).unwrap_or('?'));
+        if (i + 1) % width == 0 {
+            text.push('\n');
+            style_map.push('\n');
+        }
+    }
+    let mut out = text;
+    out.push_str("--- styles ---\n");
+    out.push_str(&style_map);
+    for (i, (fg, bg, modifier)) in styles.iter().enumerate() {
+        out.push_str(&format!(
+            "{}: fg={:?} bg={:?} mod={:?}\n",
+            KEYS.chars().nth(i).unwrap_or('?'),
+            fg,
+            bg,
+            modifier
+        ));
+    }
+    out
+}
+
+fn assert_snapshot(name: &str, buffer: &Buffer) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    assert_file(&path, &snapshot(buffer));
+}
+
+// Compare generated file contents with tests/golden/<name>
+pub fn assert_golden(name: &str, actual: &str) {
+    assert_file(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name), actual);
+}
+
+fn assert_file(path: &Path, actual: &str) {
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("missing {}, run with UPDATE_SNAPSHOTS=1", path.display()));
+    if expected != actual {
+        panic!(
+            "{} changed, run with UPDATE_SNAPSHOTS=1 to accept\n--- expected\n{}\n--- actual\n{}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+}
+
+// Every screen worth a snapshot, as the keys that reach it
+fn screens() -> Vec<(&'static str, App)> {
+    let start = || ScriptedEvents::new().key(KeyCode::Enter);
+    let mut saving_error = app_after(answer_everything(ScriptedEvents::new()).key(KeyCode::Enter));
+    saving_error.save_error = Some("Permission denied (os error 13)".to_string());
+    let mut done = app_after(answer_everything(ScriptedEvents::new()).key(KeyCode::Enter));
+    done.screen = Screen::Done;
+    vec![
+        ("splash", App::new(PathBuf::from("/tmp/arch_config.toml"), &SystemInfo::default())),
+        ("free_text", app_after(start().text("archbox"))),
+        (
+            "choice_filtered",
+            press(skip_to(app_after(start()), "Timezone Region"), ScriptedEvents::new().text("a")),
+        ),
+        (
+            "multi_select",
+            press(
+                skip_to(app_after(start()), "Locales"),
+                ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Char(' ')),
+            ),
+        ),
+        (
+            "keyboard",
+            press(skip_to(app_after(start()), "Console Keymap"), ScriptedEvents::new().key(KeyCode::Tab).text("qzy")),
+        ),
+        (
+            "number",
+            press(
+                skip_to(app_after(start()), "Btrfs Compression Level"),
+                ScriptedEvents::new().key(KeyCode::Up),
+            ),
+        ),
+        (
+            "size_error",
+            press(
+                skip_to(app_after(start()), "EFI Partition Size"),
+                ScriptedEvents::new().text("X").key(KeyCode::Enter),
+            ),
+        ),
+        (
+            "package_groups",
+            press(
+                skip_to(app_after(start()), "Package Groups"),
+                ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Char(' ')),
+            ),
+        ),
+        (
+            "extra_packages",
+            press(skip_to(app_after(start()), "Extra Packages"), ScriptedEvents::new().text("htop, vim")),
+        ),
+        ("mirror_sort", skip_to(app_after(start()), "Mirror Sort")),
+        (
+            "unknown_package",
+            press(
+                skip_to(app_with_packages(), "Extra Packages"),
+                ScriptedEvents::new().text("htop vmi").key(KeyCode::Enter),
+            ),
+        ),
+        ("boolean", skip_to(app_after(start()), "Enable SSH")),
+        ("services", skip_to(app_after(start()), "Services")),
+        // One note line per ticked kernel
+        (
+            "kernels",
+            press(
+                skip_to(app_after(start()), "Kernels"),
+                ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Char(' ')).key(KeyCode::Down).key(KeyCode::Char(' ')),
+            ),
+        ),
+        ("kernel_params", skip_to(app_after(start()), "Kernel Parameters")),
+        ("grub_theme", skip_to(app_after(start()), "GRUB Theme")),
+        // A two line note under yes/no
+        ("secure_boot", {
+            let system = SystemInfo {
+                setup_mode: Some(false),
+                ..SystemInfo::default()
+            };
+            skip_to(press(App::new(PathBuf::from("/tmp/arch_config.toml"), &system), start()), "Secure Boot")
+        }),
+        ("review", app_after(answer_everything(ScriptedEvents::new()))),
+        (
+            "review_scrolled",
+            app_after(answer_everything(ScriptedEvents::new()).key(KeyCode::PageDown).key(KeyCode::PageDown)),
+        ),
+        // The mkinitcpio preview comes last
+        ("review_end", app_after((0..10).fold(answer_everything(ScriptedEvents::new()), |keys, _| keys.key(KeyCode::PageDown)))),
+        ("saving_error", saving_error),
+        ("done", done),
+    ]
+}
+
+#[test]
+fn wizard_writes_the_answers() {
+    let dir = temp_dir("wizard");
+    let output = dir.join("arch_config.toml");
+    let mut app = App::new(output.clone(), &SystemInfo::default());
+    let events = answer_everything(ScriptedEvents::new())
+        .key(KeyCode::Enter) // save from the review screen
+        .key(KeyCode::Enter); // leave the done screen
+    run_script(&mut app, events);
+
+    assert_eq!(app.screen, Screen::Done);
+    assert_eq!(app.config, expected_config());
+    let written: ArchConfig = toml::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(written, expected_config());
+    let mode = fs::metadata(&output).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+    let script = fs::read_to_string(dir.join("arch_config_files/root/aur-setup.sh")).unwrap();
+    assert!(script.contains("paru -S --needed --noconfirm 'spotify'"));
+}
+
+#[test]
+fn saving_again_keeps_a_backup() {
+    let dir = temp_dir("backup");
+    let output = dir.join("arch_config.toml");
+    fs::write(&output, "old = true\n").unwrap();
+    let mut app = App::new(output.clone(), &SystemInfo::default());
+    run_script(&mut app, answer_everything(ScriptedEvents::new()).key(KeyCode::Enter).key(KeyCode::Enter));
+
+    assert_eq!(fs::read_to_string(dir.join("arch_config.toml.bak")).unwrap(), "old = true\n");
+    assert!(!dir.join(".arch_config.toml.tmp").exists());
+}
+
+#[test]
+fn failed_save_shows_error_and_allows_quitting() {
+    let dir = temp_dir("unwritable");
+    let output = dir.join("missing").join("arch_config.toml");
+    let mut app = App::new(output.clone(), &SystemInfo::default());
+    let events = answer_everything(ScriptedEvents::new())
+        .key(KeyCode::Enter) // save, fails
+        .key(KeyCode::Char('r')) // retry, fails again
+        .key(KeyCode::Esc);
+    let terminal = run_script(&mut app, events);
+
+    assert!(app.should_quit);
+    assert!(app.save_error.is_some());
+    assert!(!output.exists());
+    let screen = snapshot(terminal.backend().buffer());
+    assert!(screen.contains("Failed to save the configuration"));
+}
+
+#[test]
+fn quitting_early_writes_nothing() {
+    let dir = temp_dir("quit");
+    let output = dir.join("arch_config.toml");
+    let mut app = App::new(output.clone(), &SystemInfo::default());
+    run_script(&mut app, ScriptedEvents::new().key(KeyCode::Enter).text("host~").ctrl('c'));
+    assert!(!output.exists());
+}
+
+#[test]
+fn unknown_packages_are_refused() {
+    let app = press(
+        skip_to(app_with_packages(), "Extra Packages"),
+        ScriptedEvents::new().text("htop vmi").key(KeyCode::Enter),
+    );
+    assert_eq!(app.question().unwrap().prompt, "Extra Packages");
+    assert_eq!(app.message.as_deref(), Some("Unknown package 'vmi', did you mean vim?"));
+
+    let keys = ScriptedEvents::new().key(KeyCode::Backspace).key(KeyCode::Backspace).text("im").key(KeyCode::Enter);
+    let app = press(app, keys);
+    assert_ne!(app.question().unwrap().prompt, "Extra Packages");
+    assert!(app.config.packages.contains(&"vim".to_string()));
+}
+
+#[test]
+fn network_interfaces_come_from_sysfs() {
+    let interface = |name: &str, wireless, up| Interface {
+        name: name.to_string(),
+        wireless,
+        mac: "52:54:00:12:34:56".to_string(),
+        up,
+    };
+    let system = SystemInfo {
+        interfaces: vec![interface("enp1s0", false, true), interface("wlan0", true, false)],
+        ..SystemInfo::default()
+    };
+    let at_interface = || {
+        let app = App::new(PathBuf::from("/tmp/arch_config.toml"), &system);
+        skip_to(press(app, ScriptedEvents::new().key(KeyCode::Enter)), "Network Interface")
+    };
+    let app = at_interface();
+    assert_eq!(app.options()[app.selected_option], "enp1s0 (up, 52:54:00:12:34:56)");
+
+    // A wired interface skips the Wi-Fi questions
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Enter).key(KeyCode::Enter));
+    assert_eq!(app.config.network.interface, "enp1s0");
+    assert_eq!(app.question().unwrap().prompt, "Enable SSH");
+
+    let app = press(at_interface(), ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Enter).key(KeyCode::Enter));
+    assert_eq!(app.config.network.interface, "wlan0");
+    assert_eq!(app.question().unwrap().prompt, "Wi-Fi Network (SSID)");
+}
+
+#[test]
+fn hibernation_needs_swap_for_the_ram() {
+    let system = SystemInfo {
+        memory: Some((8 << 30) - (200 << 20)),
+        ..SystemInfo::default()
+    };
+    let app = App::new(PathBuf::from("/tmp/arch_config.toml"), &system);
+    let app = skip_to(press(app, ScriptedEvents::new().key(KeyCode::Enter)), "Swap");
+    // A swapfile, hibernating to it
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Enter).key(KeyCode::Up).key(KeyCode::Enter));
+    assert!(app.config.swap.hibernate);
+    assert_eq!(app.question().unwrap().prompt, "Swap Size");
+    assert_eq!(app.input_value, "8G");
+
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Backspace).key(KeyCode::Backspace).text("4G").key(KeyCode::Enter));
+    assert_eq!(app.message.as_deref(), Some("Hibernation needs at least the 7992M of RAM"));
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Backspace).key(KeyCode::Backspace).text("8G").key(KeyCode::Enter));
+    assert_eq!(app.config.swap.size, Size::gibibytes(8));
+    assert_eq!(app.config.swap.kind, SwapKind::Swapfile);
+}
+
+#[test]
+fn only_a_swap_partition_takes_a_share_of_the_disk() {
+    let at_swap = || skip_to(app_after(ScriptedEvents::new().key(KeyCode::Enter)), "Swap");
+    let clear = |keys: ScriptedEvents| (0..4).fold(keys, |keys, _| keys.key(KeyCode::Backspace));
+    // A swapfile without hibernation
+    let app = press(at_swap(), ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Enter).key(KeyCode::Enter));
+    let app = press(app, clear(ScriptedEvents::new()).text("25%").key(KeyCode::Enter));
+    assert_eq!(app.message.as_deref(), Some("A swapfile needs a size like 8G"));
+
+    // A partition, hibernating to it
+    let app = press(at_swap(), ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Enter));
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Up).key(KeyCode::Enter));
+    let app = press(app, clear(ScriptedEvents::new()).text("25%").key(KeyCode::Enter));
+    assert_eq!(app.message.as_deref(), Some("Hibernation needs a size like 8G, at least the RAM"));
+
+    // A partition for swap only
+    let app = press(at_swap(), ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Enter).key(KeyCode::Enter));
+    assert_eq!(app.question().unwrap().prompt, "Swap Size");
+    let app = press(app, clear(ScriptedEvents::new()).text("25%").key(KeyCode::Enter));
+    assert_eq!(app.config.swap.size, Size::Percent(25));
+    assert_eq!(app.config.swap.kind, SwapKind::Partition);
+}
+
+#[test]
+fn secure_boot_only_on_uefi() {
+    let root = temp_dir("secure-boot");
+    write_efivar(&root, "SetupMode", 1);
+    let system = SystemInfo::detect(&root);
+    let app = App::new(PathBuf::from("/tmp/arch_config.toml"), &system);
+    let app = skip_to(press(app, ScriptedEvents::new().key(KeyCode::Enter)), "Secure Boot");
+    let note = app.question().unwrap().note(&app.config, &app.draft_answer()).unwrap();
+    assert!(note.contains("in setup mode"));
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Up).key(KeyCode::Enter).key(KeyCode::Enter));
+    assert_eq!(app.config.secure_boot, Some(SecureBootConfig { microsoft_keys: true }));
+
+    // BIOS: grub goes to the boot sector and there is nothing to sign
+    let system = SystemInfo {
+        uefi: false,
+        ..SystemInfo::default()
+    };
+    let app = App::new(PathBuf::from("/tmp/arch_config.toml"), &system);
+    let app = skip_to(press(app, ScriptedEvents::new().key(KeyCode::Enter)), "Bootloader");
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Enter));
+    assert!(app.message.as_deref().unwrap().contains("needs UEFI"));
+    let app = skip_to(press(app, ScriptedEvents::new().key(KeyCode::Up).key(KeyCode::Enter)), "GRUB Theme");
+    assert_eq!(app.config.grub.as_ref().unwrap().target, GrubTarget::Bios);
+    let app = press(app, ScriptedEvents::new().key(KeyCode::Enter));
+    assert_eq!(app.question().unwrap().prompt, "Package Groups");
+}
+
+#[test]
+fn ssh_keys_are_checked_and_guard_the_password_login() {
+    // SSH on, port 22
+    let at_keys = || {
+        let keys = ScriptedEvents::new().key(KeyCode::Enter).key(KeyCode::Enter);
+        press(skip_to(app_after(ScriptedEvents::new().key(KeyCode::Enter)), "Enable SSH"), keys)
+    };
+    // No keys and no password login would lock everyone out
+    let app = press(at_keys(), ScriptedEvents::new().key(KeyCode::Enter).key(KeyCode::Down).key(KeyCode::Enter));
+    assert_eq!(app.question().unwrap().prompt, "SSH Password Login");
+    assert!(app.message.as_deref().unwrap().contains("authorized key"));
+
+    let app = press(at_keys(), ScriptedEvents::new().text("ssh-dss AAAA").key(KeyCode::Enter));
+    assert_eq!(app.message.as_deref(), Some("Unknown key type 'ssh-dss'"));
+
+    // With a key the password login is off unless asked for
+    let keys = ScriptedEvents::new().text(ED25519).key(KeyCode::Enter).key(KeyCode::Enter).key(KeyCode::Enter);
+    let app = press(press(at_keys(), keys), ScriptedEvents::new().key(KeyCode::Enter).key(KeyCode::Enter));
+    assert_eq!(app.screen, Screen::Review);
+    let ssh = app.config.ssh.unwrap();
+    assert_eq!(ssh.authorized_keys, vec![ED25519]);
+    assert!(!ssh.password_authentication);
+    assert_eq!(ssh.permit_root_login, RootLogin::No);
+}
+
+#[test]
+fn screens_match_snapshots() {
+    let themes = [("default", Theme::default()), ("dark", Theme::dark()), ("light", Theme::light())];
+    for (screen, mut app) in screens() {
+        for (theme_name, theme) in &themes {
+            app.theme = theme.clone();
+            assert_snapshot(&format!("{}_{}", screen, theme_name), &render(&app));
+        }
+    }
+}
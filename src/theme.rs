@@ -0,0 +1,59 @@
+// Colour themes for the UI
+use ratatui::style::Color;
+
+// Add a theme struct to store the colors for the UI
+#[derive(Clone)]
+pub struct Theme {
+    pub background: Color,
+    pub foreground: Color,
+    pub highlight: Color,
+    pub accent: Color,
+    pub text: Color,
+}
+// Define some preset themes
+impl Theme {
+    pub fn default() -> Self {
+        Theme {
+            background: Color::Reset,
+            foreground: Color::White,
+            highlight: Color::Yellow,
+            accent: Color::Green,
+            text: Color::Gray,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Theme {
+            background: Color::Black,
+            foreground: Color::White,
+            highlight: Color::Yellow,
+            accent: Color::Blue,
+            text: Color::Gray,
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            background: Color::White,
+            foreground: Color::Black,
+            highlight: Color::Red,
+            accent: Color::Blue,
+            text: Color::DarkGray,
+        }
+    }
+
+    // Cycle default -> dark -> light -> default
+    pub fn next(&self) -> Self {
+        match self.background {
+            Color::Reset => Theme::dark(),
+            Color::Black => Theme::light(),
+            _ => Theme::default(),
+        }
+    }
+}
+// Implement Default trait for Theme
+impl Default for Theme {
+    fn default() -> Self {
+        Self::default()
+    }
+}
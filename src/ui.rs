@@ -0,0 +1,334 @@
+// Rendering of the app state, nothing in here changes the state
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    app::{App, Screen},
+    generate,
+    questions::{Question, QuestionType},
+    theme::Theme,
+};
+
+// Draw the current screen
+pub fn draw<B: Backend>(app: &App, f: &mut Frame<B>) {
+    match app.screen {
+        Screen::Splash => draw_splash_screen(f, &app.theme),
+        Screen::Question => draw_main(f, app, draw_question),
+        Screen::Review => draw_main(f, app, draw_review),
+        Screen::Saving => draw_main(f, app, draw_saving),
+        Screen::Done => draw_main(f, app, draw_done),
+    }
+}
+
+// Draw the main block with a body and footer area
+fn draw_main<B: Backend>(f: &mut Frame<B>, app: &App, body: fn(&mut Frame<B>, &App, Rect, Rect)) {
+    let size = f.size();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)].as_ref())
+        .split(size);
+    // Create the main block (border around the entire UI)
+    let main_block = Block::default()
+        .title("Arch Linux Installer")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(app.theme.background));
+    body(f, app, chunks[0], chunks[1]);
+    f.render_widget(main_block, size);
+}
+
+// Render the footer with the key help
+fn draw_footer<B: Backend>(f: &mut Frame<B>, theme: &Theme, area: Rect, help: &str, filter: &str) {
+    let footer = Paragraph::new(Spans::from(vec![
+        Span::raw(help.to_string()),
+        Span::styled(filter.to_string(), Style::default().fg(theme.highlight)),
+    ]))
+    .block(Block::default().borders(Borders::ALL).style(Style::default().bg(theme.background)));
+    f.render_widget(footer, area);
+}
+
+// Helper function to draw the splash screen
+fn draw_splash_screen<B: Backend>(f: &mut Frame<B>, theme: &Theme) {
+    // Get the size of the terminal
+    let size = f.size();
+    let block = Block::default().borders(Borders::ALL);
+    f.render_widget(block, size);
+
+    let logo = r#"
+    _____                .__      .____    .__                     
+   /  _  \   _______  __ |  |__   |    |   |__| ____  __ _____  ___
+  /  /_\  \ /___/  / |  ||  |  \  |    |   |  |/    \|  |  \  \/  /
+ /    |    <  /\  /_/  ||   Y  \ |    |___|  |   |  \  |  />    < 
+/\____|__  /\___  /|____|___|  / |_______ \__|___|  /____//__/\_ \
+        \/     \/           \/          \/       \/            \/
+"#;
+    // Split the logo into lines
+    let logo_lines: Vec<&str> = logo.lines().collect();
+    let logo_height = logo_lines.len() as u16;
+    let logo_width = logo_lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+    // Calculate the padding for the logo
+    let vertical_padding = (size.height.saturating_sub(logo_height)) / 2;
+    let horizontal_padding = (size.width.saturating_sub(logo_width)) / 2;
+    // Render the logo
+    for (i, line) in logo_lines.iter().enumerate() {
+        let y = vertical_padding + i as u16;
+        if y < size.height {
+            let text = Paragraph::new(line.to_string())
+                .style(Style::default().fg(theme.accent))
+                .alignment(Alignment::Left);
+            let area = Rect::new(horizontal_padding, y, logo_width.min(size.width), 1);
+            f.render_widget(text, area);
+        }
+    }
+}
+
+// Draw the current question
+fn draw_question<B: Backend>(f: &mut Frame<B>, app: &App, body: Rect, footer: Rect) {
+    let Some(question) = app.question() else {
+        return;
+    };
+    let theme = &app.theme;
+    // Create the inner block (border around the question), multi select shows the count
+    let title = match question.question_type {
+        QuestionType::MultiSelect { .. } => format!("{} ({} selected)", question.prompt, app.checked.len()),
+        _ => question.prompt.to_string(),
+    };
+    let inner_block = Block::default().borders(Borders::ALL).title(title);
+
+    // Render the appropriate widget based on the question type
+    match &question.question_type {
+        QuestionType::MultipleChoice { .. } | QuestionType::MultiSelect { .. } | QuestionType::Boolean => {
+            draw_options(f, app, question, inner_block, body)
+        }
+        // Render the free text input, numbers and sizes get a hint below
+        QuestionType::FreeText | QuestionType::Number { .. } | QuestionType::Size { .. } => {
+            let mut text = vec![Spans::from(vec![
+                Span::styled(question.prompt, Style::default().fg(theme.highlight)),
+                Span::raw(": "),
+                Span::styled(app.input_value.as_str(), Style::default().fg(theme.accent)),
+            ])];
+            let hint = match question.question_type {
+                QuestionType::Number { min, max, step } => {
+                    Some(format!("{} to {}, Up/Down to change by {}", min, max, step))
+                }
+                QuestionType::Size { allow_percent: true } => Some("e.g. 512M, 8G or 50%".to_string()),
+                QuestionType::Size { allow_percent: false } => Some("e.g. 512M or 8G".to_string()),
+                _ => None,
+            };
+            let note = question.note(&app.config, &app.draft_answer());
+            for block in hint.into_iter().chain(note) {
+                text.push(Spans::from(""));
+                for line in block.lines() {
+                    text.push(Spans::from(Span::styled(line.to_string(), Style::default().fg(theme.text))));
+                }
+            }
+            let widget = Paragraph::new(text).block(inner_block).wrap(Wrap { trim: true });
+            f.render_widget(widget, body);
+        }
+    }
+    // A refused answer replaces the key help until the next key press
+    if let Some(message) = &app.message {
+        let widget = Paragraph::new(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+        ))
+        .block(Block::default().borders(Borders::ALL).style(Style::default().bg(theme.background)));
+        f.render_widget(widget, footer);
+        return;
+    }
+    let help = match question.question_type {
+        _ if question.test_line => "Press Enter to confirm, Tab to try the keyboard, Arrow keys to navigate, Type to filter:",
+        QuestionType::FreeText | QuestionType::Number { .. } | QuestionType::Size { .. } => {
+            "Press Enter to confirm, Backspace to delete, Ctrl+C to quit"
+        }
+        QuestionType::MultiSelect { .. } => {
+            "Space to select, Ctrl+A/Ctrl+N all/none, Enter to confirm, '~' to quit, Type to filter:"
+        }
+        _ => "Press Enter to confirm, Arrow keys to navigate, '~' to quit, '*' to change theme, Type to filter:",
+    };
+    draw_footer(f, theme, footer, help, &app.filter);
+}
+
+// Render the list of options for choice and yes/no questions
+fn draw_options<B: Backend>(f: &mut Frame<B>, app: &App, question: &Question, block: Block, area: Rect) {
+    let theme = &app.theme;
+    // Add question prompt
+    let question_prompt = Paragraph::new(question.prompt)
+        .style(Style::default().fg(theme.accent))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(question_prompt, area);
+    // The note goes below the list, as tall as its wrapped lines but never less than the usual 2 rows
+    let note = question.note(&app.config, &app.draft_answer());
+    let note_width = usize::from(area.width.saturating_sub(2)).max(1);
+    let note_rows = note
+        .iter()
+        .flat_map(|note| note.lines())
+        .map(|line| line.chars().count().max(1).div_ceil(note_width))
+        .sum::<usize>()
+        .max(2);
+    let note_height = u16::try_from(note_rows + 1).unwrap_or(u16::MAX);
+    // Adjust the chunks to make room for the question prompt and the note
+    let inner_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(note_height)].as_ref())
+        .split(area);
+    // Yes/No questions don't use the highlight colour for the selection
+    let (selected_fg, other_fg) = match question.question_type {
+        QuestionType::Boolean => (theme.accent, theme.text),
+        _ => (theme.highlight, theme.accent),
+    };
+    // Create the list widget
+    let items: Vec<ListItem> = app
+        .options()
+        .into_iter()
+        .enumerate()
+        .map(|(i, option)| {
+            let style = if i == app.selected_option {
+                Style::default().fg(selected_fg).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(other_fg)
+            };
+            // Multi select options get a checkbox
+            let label = match question.question_type {
+                QuestionType::MultiSelect { .. } => {
+                    let mark = if app.checked.contains(&option) { "[x] " } else { "[ ] " };
+                    format!("{}{}", mark, option)
+                }
+                _ => option,
+            };
+            ListItem::new(Spans::from(vec![Span::styled(label, style)]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
+        .highlight_symbol("> ");
+    // Render the list of options
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_option));
+    let mut list_area = inner_chunks[1];
+    // Keyboard questions get a line to try out the keys
+    if question.test_line {
+        let test_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(3)].as_ref())
+            .split(list_area);
+        list_area = test_chunks[0];
+        let border = if app.test_focus { theme.highlight } else { theme.text };
+        let test_line = Paragraph::new(Span::styled(app.test_input.as_str(), Style::default().fg(theme.accent)))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border))
+                    .title("Test your keyboard (Tab to switch)"),
+            );
+        f.render_widget(test_line, test_chunks[1]);
+    }
+    f.render_stateful_widget(list, list_area, &mut list_state);
+    // One line of text per line of the note, a single span would run them together
+    if let Some(note) = note {
+        let text: Vec<Spans> = note
+            .lines()
+            .map(|line| Spans::from(Span::styled(line.to_string(), Style::default().fg(theme.text))))
+            .collect();
+        let note = Paragraph::new(text).wrap(Wrap { trim: true });
+        let area = inner_chunks[2];
+        f.render_widget(note, Rect::new(area.x + 1, area.y, area.width.saturating_sub(2), area.height.saturating_sub(1)));
+    }
+}
+
+// Show every answer before the config is written
+fn draw_review<B: Backend>(f: &mut Frame<B>, app: &App, body: Rect, footer: Rect) {
+    let theme = &app.theme;
+    let mut lines = app.config.review_lines();
+    lines.extend(generate::mkinitcpio::review_lines(&app.config));
+    lines.extend(generate::fstab::review_lines(&app.config));
+    let key_width = lines.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    // Long values wrap, count the rows they take up
+    let inner_width = usize::from(body.width.saturating_sub(2)).max(1);
+    let rows: usize =
+        lines.iter().map(|(_, value)| (key_width + 3 + value.chars().count()).div_ceil(inner_width).max(1)).sum();
+    let text: Vec<Spans> = lines
+        .into_iter()
+        .map(|(key, value)| {
+            Spans::from(vec![
+                Span::styled(format!("{:<width$}", key, width = key_width), Style::default().fg(theme.highlight)),
+                Span::raw(" = "),
+                Span::styled(value, Style::default().fg(theme.accent)),
+            ])
+        })
+        .collect();
+    // Stop scrolling once the last line is in view
+    let max_scroll = (rows as u16).saturating_sub(body.height.saturating_sub(2));
+    let widget = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title("Review"))
+        .wrap(Wrap { trim: false })
+        .scroll((app.review_scroll.min(max_scroll), 0));
+    f.render_widget(widget, body);
+    draw_footer(
+        f,
+        theme,
+        footer,
+        "Press Enter to save, Up/Down to scroll, Backspace to go back, '~' to quit without saving",
+        "",
+    );
+}
+
+// Helper function to draw the saving screen, with the error when the config can't be saved
+fn draw_saving<B: Backend>(f: &mut Frame<B>, app: &App, body: Rect, footer: Rect) {
+    let theme = &app.theme;
+    let Some(error) = &app.save_error else {
+        let text = Paragraph::new(format!("Saving to {} ...", app.output.display()))
+            .block(Block::default().borders(Borders::ALL).title("Saving"));
+        f.render_widget(text, body);
+        return;
+    };
+    let text = vec![
+        Spans::from(Span::styled(
+            "Failed to save the configuration",
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+        )),
+        Spans::from(""),
+        Spans::from(vec![
+            Span::raw("File: "),
+            Span::styled(app.output.display().to_string(), Style::default().fg(theme.accent)),
+        ]),
+        Spans::from(vec![
+            Span::raw("Error: "),
+            Span::styled(error.as_str(), Style::default().fg(theme.foreground)),
+        ]),
+    ];
+    let widget = Paragraph::new(text)
+        .block(Block::default().title("Error").borders(Borders::ALL))
+        .wrap(Wrap { trim: true });
+    f.render_widget(widget, body);
+    draw_footer(
+        f,
+        theme,
+        footer,
+        "Press 'r' or Enter to retry, '~' or Esc to quit without saving",
+        "",
+    );
+}
+
+// Confirm where the config went
+fn draw_done<B: Backend>(f: &mut Frame<B>, app: &App, body: Rect, footer: Rect) {
+    let text = vec![
+        Spans::from(vec![
+            Span::raw("Configuration saved to "),
+            Span::styled(app.output.display().to_string(), Style::default().fg(app.theme.accent)),
+        ]),
+        Spans::from(vec![
+            Span::raw("Files for the new system in "),
+            Span::styled(generate::output_dir(&app.output).display().to_string(), Style::default().fg(app.theme.accent)),
+        ]),
+    ];
+    let widget = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Done"));
+    f.render_widget(widget, body);
+    draw_footer(f, &app.theme, footer, "Press any key to exit", "");
+}
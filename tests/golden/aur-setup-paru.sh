@@ -0,0 +1,21 @@
+#!/bin/bash
+# Build the paru AUR helper and install the AUR packages.
+# Run as root on the installed system, it needs the network.
+set -euo pipefail
+
+BUILD_USER='sam'
+SUDOERS=/etc/sudoers.d/90-aur-setup
+
+pacman -S --needed --noconfirm base-devel git sudo
+
+# Let the build user install what it builds until the script ends
+echo "$BUILD_USER ALL=(ALL) NOPASSWD: /usr/bin/pacman" > "$SUDOERS"
+chmod 0440 "$SUDOERS"
+trap 'rm -f "$SUDOERS"' EXIT
+
+BUILD_DIR=$(sudo -u "$BUILD_USER" mktemp -d)
+sudo -u "$BUILD_USER" git clone https://aur.archlinux.org/paru.git "$BUILD_DIR/paru"
+(cd "$BUILD_DIR/paru" && sudo -u "$BUILD_USER" makepkg -si --noconfirm)
+rm -rf "$BUILD_DIR"
+
+sudo -u "$BUILD_USER" paru -S --needed --noconfirm 'visual-studio-code-bin' 'spotify'
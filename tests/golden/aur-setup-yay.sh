@@ -0,0 +1,19 @@
+#!/bin/bash
+# Build the yay AUR helper and install the AUR packages.
+# Run as root on the installed system, it needs the network.
+set -euo pipefail
+
+BUILD_USER='sam'
+SUDOERS=/etc/sudoers.d/90-aur-setup
+
+pacman -S --needed --noconfirm base-devel git sudo
+
+# Let the build user install what it builds until the script ends
+echo "$BUILD_USER ALL=(ALL) NOPASSWD: /usr/bin/pacman" > "$SUDOERS"
+chmod 0440 "$SUDOERS"
+trap 'rm -f "$SUDOERS"' EXIT
+
+BUILD_DIR=$(sudo -u "$BUILD_USER" mktemp -d)
+sudo -u "$BUILD_USER" git clone https://aur.archlinux.org/yay.git "$BUILD_DIR/yay"
+(cd "$BUILD_DIR/yay" && sudo -u "$BUILD_USER" makepkg -si --noconfirm)
+rm -rf "$BUILD_DIR"
@@ -0,0 +1,11 @@
+#!/bin/bash
+# Install the bootloader, run inside arch-chroot.
+set -euo pipefail
+
+root_uuid=$(findmnt -no UUID /)
+sed -i -e "s|@ROOT_UUID@|$root_uuid|g" /etc/fstab /etc/default/grub
+pacman -S --needed --noconfirm grub
+disk=$(lsblk -lnspo NAME,TYPE "$(findmnt -nvo SOURCE /)" | awk '$2 == "disk" { print $1; exit }')
+grub-install --target=i386-pc "$disk"
+grub-mkconfig -o /boot/grub/grub.cfg
+mkinitcpio -P
@@ -0,0 +1,12 @@
+#!/bin/bash
+# Install the bootloader, run inside arch-chroot.
+set -euo pipefail
+
+root_uuid=$(findmnt -no UUID /)
+efi_uuid=$(findmnt -no UUID /boot)
+luks_uuid=$(blkid -t TYPE=crypto_LUKS -s UUID -o value | head -n 1)
+sed -i -e "s|@ROOT_UUID@|$root_uuid|g" -e "s|@EFI_UUID@|$efi_uuid|g" -e "s|@LUKS_UUID@|$luks_uuid|g" /etc/fstab /etc/default/grub
+pacman -S --needed --noconfirm grub efibootmgr os-prober
+grub-install --target=x86_64-efi --efi-directory=/boot --bootloader-id=GRUB
+grub-mkconfig -o /boot/grub/grub.cfg
+mkinitcpio -P
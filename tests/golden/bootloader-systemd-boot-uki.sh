@@ -0,0 +1,16 @@
+#!/bin/bash
+# Install the bootloader, run inside arch-chroot.
+set -euo pipefail
+
+root_uuid=$(findmnt -no UUID /)
+efi_uuid=$(findmnt -no UUID /boot)
+sed -i -e "s|@ROOT_UUID@|$root_uuid|g" -e "s|@EFI_UUID@|$efi_uuid|g" /etc/fstab /etc/kernel/cmdline
+bootctl install
+mkinitcpio -P
+pacman -S --needed --noconfirm sbctl
+sbctl create-keys
+sbctl enroll-keys --microsoft
+sbctl sign -s /boot/EFI/systemd/systemd-bootx64.efi
+sbctl sign -s /boot/EFI/BOOT/BOOTX64.EFI
+sbctl sign -s /boot/EFI/Linux/arch-linux.efi
+sbctl sign -s /boot/EFI/Linux/arch-linux-lts.efi
@@ -0,0 +1,10 @@
+#!/bin/bash
+# Install the bootloader, run inside arch-chroot.
+set -euo pipefail
+
+root_uuid=$(findmnt -no UUID /)
+efi_uuid=$(findmnt -no UUID /boot)
+resume_offset=$(btrfs inspect-internal map-swapfile -r /swap/swapfile)
+sed -i -e "s|@ROOT_UUID@|$root_uuid|g" -e "s|@EFI_UUID@|$efi_uuid|g" -e "s|@RESUME_OFFSET@|$resume_offset|g" /etc/fstab /boot/loader/entries/arch-linux.conf /boot/loader/entries/arch-linux-lts.conf
+bootctl install
+mkinitcpio -P
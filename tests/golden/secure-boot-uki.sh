@@ -0,0 +1,7 @@
+pacman -S --needed --noconfirm sbctl
+sbctl create-keys
+sbctl enroll-keys
+sbctl sign -s /boot/EFI/systemd/systemd-bootx64.efi
+sbctl sign -s /boot/EFI/BOOT/BOOTX64.EFI
+sbctl sign -s /boot/EFI/Linux/arch-linux.efi
+sbctl sign -s /boot/EFI/Linux/arch-linux-lts.efi
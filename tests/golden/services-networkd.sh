@@ -0,0 +1,6 @@
+#!/bin/bash
+# Install and enable the services picked in the menu, run inside arch-chroot.
+set -euo pipefail
+
+pacman -S --needed --noconfirm zram-generator
+systemctl enable systemd-networkd.service systemd-resolved.service
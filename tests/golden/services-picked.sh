@@ -0,0 +1,7 @@
+#!/bin/bash
+# Install and enable the services picked in the menu, run inside arch-chroot.
+set -euo pipefail
+
+pacman -S --needed --noconfirm networkmanager zram-generator snapper
+systemctl enable NetworkManager.service fstrim.timer snapper-timeline.timer snapper-cleanup.timer
+systemctl disable systemd-timesyncd.service
@@ -0,0 +1,7 @@
+#!/bin/bash
+# Create the swapfile, run inside arch-chroot.
+set -euo pipefail
+
+btrfs subvolume create /swap
+btrfs filesystem mkswapfile --size 8G --uuid clear /swap/swapfile
+echo "resume=UUID=$(findmnt -no UUID -T /swap/swapfile) resume_offset=$(btrfs inspect-internal map-swapfile -r /swap/swapfile)"
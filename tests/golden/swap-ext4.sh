@@ -0,0 +1,5 @@
+#!/bin/bash
+# Create the swapfile, run inside arch-chroot.
+set -euo pipefail
+
+mkswap --file --size 8G /swapfile